@@ -0,0 +1,185 @@
+use crate::commands::{CommandEvent, CommandResult, ShellCommand};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// A package as returned by the AUR RPC `search`/`info` endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AurPackage {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Version")]
+    pub version: String,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "Depends", default)]
+    pub depends: Vec<String>,
+    #[serde(rename = "MakeDepends", default)]
+    pub make_depends: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AurSearchResponse {
+    results: Vec<AurPackage>,
+}
+
+const AUR_RPC_URL: &str = "https://aur.archlinux.org/rpc/?v=5";
+
+/// Search the AUR for packages matching `term`
+pub async fn search(term: &str) -> Result<Vec<AurPackage>, AppError> {
+    let url = format!("{}&type=search&arg={}", AUR_RPC_URL, urlencoding_encode(term));
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?
+        .json::<AurSearchResponse>()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    Ok(response.results)
+}
+
+/// Minimal percent-encoding for a search term in a query string
+fn urlencoding_encode(term: &str) -> String {
+    let mut out = String::with_capacity(term.len());
+    for byte in term.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char);
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Validate `pkg` against pacman's allowed package-name charset
+/// (`[a-zA-Z0-9@._+-]+`, not starting with `.` or `-`) before it's used to
+/// build a cache path or clone URL.
+///
+/// `pkg` comes straight off the network (the AUR RPC's `Name` field, or a
+/// foreign package name from `pacman -Qm`), so without this check a crafted
+/// name containing `/` or `..` could escape `~/.cache/vistula-updater/aur`.
+pub fn validate_pkg_name(pkg: &str) -> Result<(), AppError> {
+    let valid = !pkg.is_empty()
+        && !pkg.starts_with('.')
+        && !pkg.starts_with('-')
+        && pkg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '+' | '-'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Other(format!("invalid AUR package name: {:?}", pkg)))
+    }
+}
+
+/// Clone/pull `pkg`'s AUR git repo, then build and install it with
+/// `makepkg -si`, streaming both steps' output through one combined
+/// [`commands::CommandEvent`] channel so the caller can't tell where the
+/// `git` step ends and the `makepkg` step begins.
+pub async fn install_streaming(pkg: &str) -> Result<mpsc::UnboundedReceiver<CommandEvent>, AppError> {
+    validate_pkg_name(pkg)?;
+
+    let cache_dir = std::env::var("HOME")
+        .map(|home| std::path::PathBuf::from(home).join(".cache/vistula-updater/aur"))
+        .map_err(|e| AppError::Other(e.to_string()))?;
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let pkg_dir = cache_dir.join(pkg);
+    let pkg_dir_str = pkg_dir
+        .to_str()
+        .ok_or_else(|| AppError::Other("invalid package cache path".to_string()))?
+        .to_string();
+
+    let git_step = if pkg_dir.exists() {
+        ShellCommand::new("git").args(["-C", pkg_dir_str.as_str(), "pull"])
+    } else {
+        let clone_url = format!("https://aur.archlinux.org/{}.git", pkg);
+        ShellCommand::new("git").args(["clone", clone_url.as_str(), pkg_dir_str.as_str()])
+    };
+
+    let mut git_rx = git_step.run().await?;
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let git_ok = loop {
+            match git_rx.recv().await {
+                Some(CommandEvent::Finished { status }) => break status.success(),
+                Some(event) => {
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                }
+                None => break false,
+            }
+        };
+
+        if !git_ok {
+            let _ = tx.send(CommandEvent::Finished {
+                status: CommandResult { exit_code: -1, stdout: String::new(), stderr: String::new() },
+            });
+            return;
+        }
+
+        match ShellCommand::new("makepkg")
+            .args(["-si", "--noconfirm"])
+            .current_dir(pkg_dir)
+            .run()
+            .await
+        {
+            Ok(mut makepkg_rx) => {
+                while let Some(event) = makepkg_rx.recv().await {
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("failed to start makepkg: {}", e);
+                let _ = tx.send(CommandEvent::Finished {
+                    status: CommandResult { exit_code: -1, stdout: String::new(), stderr: e.to_string() },
+                });
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urlencoding_encode() {
+        assert_eq!(urlencoding_encode("yay"), "yay");
+        assert_eq!(urlencoding_encode("visual studio"), "visual%20studio");
+    }
+
+    #[test]
+    fn test_aur_package_deserialize() {
+        let json = r#"{"Name":"yay","Version":"12.3.5-1","Description":"Yet another yogurt","Depends":["pacman"],"MakeDepends":["go"]}"#;
+        let pkg: AurPackage = serde_json::from_str(json).unwrap();
+        assert_eq!(pkg.name, "yay");
+        assert_eq!(pkg.depends, vec!["pacman".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_pkg_name_accepts_real_names() {
+        assert!(validate_pkg_name("yay").is_ok());
+        assert!(validate_pkg_name("visual-studio-code-bin").is_ok());
+        assert!(validate_pkg_name("lib32-glibc").is_ok());
+        assert!(validate_pkg_name("perl-ipc-run3").is_ok());
+        assert!(validate_pkg_name("a.b+c@1").is_ok());
+    }
+
+    #[test]
+    fn test_validate_pkg_name_rejects_path_escapes() {
+        assert!(validate_pkg_name("../../etc/passwd").is_err());
+        assert!(validate_pkg_name("foo/bar").is_err());
+        assert!(validate_pkg_name("..").is_err());
+        assert!(validate_pkg_name("").is_err());
+        assert!(validate_pkg_name("-rf").is_err());
+        assert!(validate_pkg_name(".hidden").is_err());
+    }
+}
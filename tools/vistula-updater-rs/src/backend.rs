@@ -0,0 +1,177 @@
+use crate::error::AppError;
+use crate::pacman::PackageUpdate;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// A pluggable source of packages (pacman, flatpak, AUR, a WASM extension, ...)
+#[async_trait]
+pub trait Backend: Send + Sync {
+    /// Short, human-readable identifier shown in the UI and logs
+    fn name(&self) -> &str;
+
+    /// List the updates this backend currently sees as available
+    async fn check(&self) -> Result<Vec<PackageUpdate>>;
+
+    /// Apply updates for the given package ids, or every pending update for
+    /// this backend when `ids` is empty
+    async fn apply(&self, ids: &[String]) -> Result<()>;
+}
+
+/// Built-in backend wrapping the existing pacman module
+pub struct PacmanBackend;
+
+#[async_trait]
+impl Backend for PacmanBackend {
+    fn name(&self) -> &str {
+        "pacman"
+    }
+
+    async fn check(&self) -> Result<Vec<PackageUpdate>> {
+        crate::pacman::check_for_updates()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Repo-sourced updates are covered by a blanket `pacman -Syu`, applied
+    /// when `ids` is empty. AUR-sourced updates aren't touched by `-Syu`, so
+    /// a non-empty `ids` is instead treated as the list of AUR packages to
+    /// build and install via `pacman::update_aur`.
+    async fn apply(&self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() {
+            return crate::pacman::update_system().await.map_err(|e| anyhow::anyhow!(e));
+        }
+        for id in ids {
+            crate::pacman::update_aur(id).await.map_err(|e| anyhow::anyhow!(e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Built-in backend wrapping the existing flatpak module
+pub struct FlatpakBackend;
+
+#[async_trait]
+impl Backend for FlatpakBackend {
+    fn name(&self) -> &str {
+        "flatpak"
+    }
+
+    async fn check(&self) -> Result<Vec<PackageUpdate>> {
+        // Flatpak apps don't carry version info in the same shape as
+        // PackageUpdate until they're actually updated; report installed
+        // apps that flatpak itself reports as having updates pending.
+        let result = crate::commands::run_command("flatpak", &["remote-ls", "--updates"], false)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(result
+            .stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let name = line.split_whitespace().next().unwrap_or(line).to_string();
+                PackageUpdate {
+                    name,
+                    current_version: String::new(),
+                    new_version: String::new(),
+                    source: crate::pacman::UpdateSource::Repo,
+                }
+            })
+            .collect())
+    }
+
+    async fn apply(&self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() {
+            return crate::flatpak::update_all().await.map_err(|e| anyhow::anyhow!(e));
+        }
+        crate::commands::ShellCommand::new("flatpak")
+            .arg("update")
+            .args(ids.iter().cloned())
+            .elevated(true)
+            .wait()?
+            .into_result()?;
+        Ok(())
+    }
+}
+
+/// Registry of all active backends, built-in and extension-provided
+pub struct BackendRegistry {
+    backends: Vec<Box<dyn Backend>>,
+}
+
+impl BackendRegistry {
+    /// Construct a registry containing the built-in backends
+    pub fn with_builtins() -> Self {
+        Self {
+            backends: vec![Box::new(PacmanBackend), Box::new(FlatpakBackend)],
+        }
+    }
+
+    /// Register an additional backend (e.g. a loaded WASM extension)
+    pub fn register(&mut self, backend: Box<dyn Backend>) {
+        self.backends.push(backend);
+    }
+
+    pub fn backends(&self) -> &[Box<dyn Backend>] {
+        &self.backends
+    }
+
+    /// Apply updates for the given package ids through the named backend
+    pub async fn apply_to(&self, backend_name: &str, ids: &[String]) -> Result<()> {
+        for backend in &self.backends {
+            if backend.name() == backend_name {
+                return backend.apply(ids).await;
+            }
+        }
+        Err(anyhow::anyhow!("no backend named {backend_name}"))
+    }
+
+    /// Check every registered backend, keeping each backend's result separate
+    /// so the UI can attribute failures to their source.
+    pub async fn check_all(&self) -> Vec<(String, Result<Vec<PackageUpdate>>)> {
+        let mut results = Vec::with_capacity(self.backends.len());
+        for backend in &self.backends {
+            results.push((backend.name().to_string(), backend.check().await));
+        }
+        results
+    }
+}
+
+impl Default for BackendRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+/// Check every backend and flatten the results into the `Result<_, AppError>`
+/// shape the rest of the UI layer expects. When at least one backend reports
+/// updates, those are returned and the other backends' failures are dropped;
+/// when no update is found, any failures are joined into a single
+/// [`AppError::Other`].
+pub async fn check_all_flattened(registry: &BackendRegistry) -> Result<Vec<PackageUpdate>, AppError> {
+    let mut updates = Vec::new();
+    let mut errors = Vec::new();
+
+    for (name, result) in registry.check_all().await {
+        match result {
+            Ok(mut backend_updates) => updates.append(&mut backend_updates),
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+
+    if updates.is_empty() && !errors.is_empty() {
+        Err(AppError::Other(errors.join("; ")))
+    } else {
+        Ok(updates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_has_builtins() {
+        let registry = BackendRegistry::with_builtins();
+        let names: Vec<&str> = registry.backends().iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["pacman", "flatpak"]);
+    }
+}
@@ -1,8 +1,9 @@
-use anyhow::Context;
+use crate::error::AppError;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
-use anyhow::Result;
 use tokio::io::AsyncBufReadExt;
 use tokio::process::Command as TokioCommand;
+use tokio::sync::mpsc;
 
 /// Check if a command/binary exists in PATH
 pub fn have_command(name: &str) -> bool {
@@ -21,70 +22,208 @@ impl CommandResult {
     pub fn success(&self) -> bool {
         self.exit_code == 0
     }
+
+    /// Turn a non-zero exit into an [`AppError::PermissionDenied`] when the
+    /// failure looks like a denied elevation request, or an
+    /// [`AppError::Command`] otherwise.
+    pub fn into_result(self) -> Result<CommandResult, AppError> {
+        if self.success() {
+            Ok(self)
+        } else if is_permission_denied(self.exit_code, &self.stderr) {
+            Err(AppError::PermissionDenied(self.stderr))
+        } else {
+            Err(AppError::Command { code: self.exit_code, stderr: self.stderr })
+        }
+    }
 }
 
-/// Run command synchronously with optional privilege escalation
-pub fn run_command(
-    cmd: &str,
-    args: &[&str],
-    use_pkexec: bool,
-) -> Result<CommandResult> {
-    let actual_cmd = if use_pkexec { "pkexec" } else { cmd };
-    let actual_args: Vec<&str> = if use_pkexec {
-        vec![cmd].into_iter().chain(args.iter().copied()).collect()
-    } else {
-        args.to_vec()
-    };
-
-    let output = Command::new(actual_cmd)
-        .args(&actual_args)
-        .output()
-        .with_context(|| format!("Failed to execute: {} {:?}", actual_cmd, actual_args))?;
+/// Recognize an elevated command's failure as a denied/dismissed
+/// authorization rather than an ordinary non-zero exit. `pkexec` exits 126
+/// when authorization couldn't be obtained and 127 when the user dismissed
+/// the prompt; `sudo` just exits 1, so its denial is only visible in stderr's
+/// wording.
+fn is_permission_denied(exit_code: i32, stderr: &str) -> bool {
+    if exit_code == 126 || exit_code == 127 {
+        return true;
+    }
+    let lower = stderr.to_lowercase();
+    lower.contains("incorrect password")
+        || lower.contains("authentication failure")
+        || lower.contains("not in the sudoers")
+        || lower.contains("request dismissed")
+}
 
-    Ok(CommandResult {
-        exit_code: output.status.code().unwrap_or(-1),
-        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-    })
+/// Which tool is used to escalate privileges, chosen from `AppConfig` so the
+/// user can pick whichever is set up on their system.
+fn elevation_tool() -> &'static str {
+    let prefers_sudo = crate::config::load_config()
+        .map(|c| c.elevation_tool == "sudo")
+        .unwrap_or(false);
+    if prefers_sudo { "sudo" } else { "pkexec" }
 }
 
-/// Run command asynchronously with callbacks
-pub async fn run_command_async<F, G>(
-    cmd: &str,
-    args: &[&str],
-    use_pkexec: bool,
+/// A shell command builder that centralizes argument assembly, privilege
+/// escalation, and environment handling for every caller in this crate.
+///
+/// ```ignore
+/// ShellCommand::new("pacman")
+///     .arg("-Syu")
+///     .elevated(true)
+///     .env("LANG", "C")
+///     .wait()?;
+/// ```
+pub struct ShellCommand {
+    program: String,
+    args: Vec<String>,
+    elevated: bool,
+    envs: Vec<(String, String)>,
+    current_dir: Option<PathBuf>,
+}
+
+impl ShellCommand {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            elevated: false,
+            envs: Vec::new(),
+            current_dir: None,
+        }
+    }
+
+    /// Append a single argument
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Append several arguments at once
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Run the command elevated, via whichever privilege-escalation tool is configured
+    pub fn elevated(mut self, yes: bool) -> Self {
+        self.elevated = yes;
+        self
+    }
+
+    /// Set an environment variable for the child process
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Run the command in `dir` instead of the current process's working directory
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Resolve the actual program and argument vector to execute, prepending
+    /// the elevation tool and the original program name when `elevated`
+    fn resolved(&self) -> (String, Vec<String>) {
+        if self.elevated {
+            let mut args = vec![self.program.clone()];
+            args.extend(self.args.iter().cloned());
+            (elevation_tool().to_string(), args)
+        } else {
+            (self.program.clone(), self.args.clone())
+        }
+    }
+
+    /// Run the command synchronously and collect its output
+    pub fn wait(self) -> Result<CommandResult, AppError> {
+        let (program, args) = self.resolved();
+
+        let mut command = Command::new(&program);
+        command.args(&args);
+        for (key, value) in &self.envs {
+            command.env(key, value);
+        }
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+
+        let output = command.output()?;
+
+        Ok(CommandResult {
+            exit_code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+
+    /// Run the command asynchronously, invoking `on_line` for each line of
+    /// stdout as it arrives and `on_done` once the process exits.
+    pub async fn wait_with_output_callback<F, G>(
+        self,
+        on_line: Option<F>,
+        on_done: G,
+    ) -> Result<(), AppError>
+    where
+        F: FnMut(String) + Send + 'static,
+        G: FnOnce(CommandResult) + Send + 'static,
+    {
+        let (program, args) = self.resolved();
+        let result =
+            exec_async_capturing(&program, &args, &self.envs, self.current_dir.as_deref(), on_line).await?;
+        on_done(result);
+        Ok(())
+    }
+
+    /// Run the command asynchronously, streaming [`CommandEvent`]s (lines,
+    /// parsed progress, and a terminal `Finished`) via the returned receiver.
+    pub async fn run(self) -> Result<mpsc::UnboundedReceiver<CommandEvent>, AppError> {
+        let (program, args) = self.resolved();
+        exec_async_streaming(&program, &args, &self.envs, self.current_dir.as_deref()).await
+    }
+}
+
+fn spawn_piped(
+    program: &str,
+    args: &[String],
+    envs: &[(String, String)],
+    current_dir: Option<&std::path::Path>,
+) -> Result<tokio::process::Child, AppError> {
+    let mut command = TokioCommand::new(program);
+    command.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
+    for (key, value) in envs {
+        command.env(key, value);
+    }
+    if let Some(dir) = current_dir {
+        command.current_dir(dir);
+    }
+    Ok(command.spawn()?)
+}
+
+/// Shared implementation behind [`ShellCommand::wait_with_output_callback`]
+/// and the legacy [`run_command_async`] free function.
+async fn exec_async_capturing<F>(
+    program: &str,
+    args: &[String],
+    envs: &[(String, String)],
+    current_dir: Option<&std::path::Path>,
     mut on_line: Option<F>,
-    on_done: G,
-) -> Result<()>
+) -> Result<CommandResult, AppError>
 where
     F: FnMut(String) + Send + 'static,
-    G: FnOnce(CommandResult) + Send + 'static,
 {
-    let actual_cmd = if use_pkexec { "pkexec" } else { cmd };
-    let actual_args: Vec<String> = if use_pkexec {
-        vec![cmd.to_string()]
-            .into_iter()
-            .chain(args.iter().map(|s| s.to_string()))
-            .collect()
-    } else {
-        args.iter().map(|s| s.to_string()).collect()
-    };
-
-    let mut child = TokioCommand::new(actual_cmd)
-        .args(&actual_args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .with_context(|| format!("Failed to spawn: {} {:?}", actual_cmd, actual_args))?;
+    let mut child = spawn_piped(program, args, envs, current_dir)?;
 
     let stdout = child
         .stdout
         .take()
-        .context("Failed to capture stdout")?;
+        .ok_or_else(|| AppError::Other("failed to capture stdout".to_string()))?;
     let stderr = child
         .stderr
         .take()
-        .context("Failed to capture stderr")?;
+        .ok_or_else(|| AppError::Other("failed to capture stderr".to_string()))?;
 
     let mut stdout_reader = tokio::io::BufReader::new(stdout).lines();
     let mut stderr_reader = tokio::io::BufReader::new(stderr).lines();
@@ -111,19 +250,194 @@ where
         } => {}
     }
 
-    let status = child
-        .wait()
-        .await
-        .context("Failed to wait for command")?;
+    let status = child.wait().await?;
 
-    let result = CommandResult {
+    Ok(CommandResult {
         exit_code: status.code().unwrap_or(-1),
         stdout: stdout_buf,
         stderr: stderr_buf,
-    };
+    })
+}
+
+/// Shared implementation behind [`ShellCommand::run`] and the legacy
+/// [`run_command_streaming`] free function.
+async fn exec_async_streaming(
+    program: &str,
+    args: &[String],
+    envs: &[(String, String)],
+    current_dir: Option<&std::path::Path>,
+) -> Result<mpsc::UnboundedReceiver<CommandEvent>, AppError> {
+    let mut child = spawn_piped(program, args, envs, current_dir)?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::Other("failed to capture stdout".to_string()))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| AppError::Other("failed to capture stderr".to_string()))?;
+
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut stdout_reader = tokio::io::BufReader::new(stdout).lines();
+        let mut stderr_reader = tokio::io::BufReader::new(stderr).lines();
+        let mut stdout_buf = String::new();
+        let mut stderr_buf = String::new();
+
+        let stdout_tx = tx.clone();
+        let read_stdout = async {
+            while let Ok(Some(line)) = stdout_reader.next_line().await {
+                if let Some(event) = parse_progress_line(&line) {
+                    let _ = stdout_tx.send(event);
+                }
+                let _ = stdout_tx.send(CommandEvent::Line(line.clone()));
+                stdout_buf.push_str(&line);
+                stdout_buf.push('\n');
+            }
+            stdout_buf
+        };
+
+        let stderr_tx = tx.clone();
+        let read_stderr = async {
+            while let Ok(Some(line)) = stderr_reader.next_line().await {
+                let _ = stderr_tx.send(CommandEvent::Line(line.clone()));
+                stderr_buf.push_str(&line);
+                stderr_buf.push('\n');
+            }
+            stderr_buf
+        };
+
+        let (stdout_buf, stderr_buf) = tokio::join!(read_stdout, read_stderr);
+
+        let status = child.wait().await;
+        let result = CommandResult {
+            exit_code: status.map(|s| s.code().unwrap_or(-1)).unwrap_or(-1),
+            stdout: stdout_buf,
+            stderr: stderr_buf,
+        };
+        let _ = tx.send(CommandEvent::Finished { status: result });
+    });
+
+    Ok(rx)
+}
+
+/// Run command synchronously with optional privilege escalation
+pub fn run_command(cmd: &str, args: &[&str], use_pkexec: bool) -> Result<CommandResult, AppError> {
+    ShellCommand::new(cmd)
+        .args(args.iter().map(|s| s.to_string()))
+        .elevated(use_pkexec)
+        .wait()
+}
+
+/// Run command asynchronously with callbacks
+pub async fn run_command_async<F, G>(
+    cmd: &str,
+    args: &[&str],
+    use_pkexec: bool,
+    on_line: Option<F>,
+    on_done: G,
+) -> Result<(), AppError>
+where
+    F: FnMut(String) + Send + 'static,
+    G: FnOnce(CommandResult) + Send + 'static,
+{
+    ShellCommand::new(cmd)
+        .args(args.iter().map(|s| s.to_string()))
+        .elevated(use_pkexec)
+        .wait_with_output_callback(on_line, on_done)
+        .await
+}
+
+/// How often the sudoloop keepalive refreshes the cached privilege timestamp
+const SUDOLOOP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Start a background task that keeps a `sudo` privilege timestamp fresh for
+/// the lifetime of a long-running elevated operation, so the user isn't
+/// re-prompted for a password partway through e.g. a big `pacman -Syu`.
+///
+/// This only has an effect when `elevation_tool` is configured as `"sudo"`:
+/// `sudo -v` refreshes sudo's own credential cache, but `pkexec` has no
+/// equivalent keepalive, so the task is a no-op under the default config.
+///
+/// Returns an [`tokio::task::AbortHandle`] the caller should abort once the
+/// elevated operation finishes.
+pub async fn start_sudoloop() -> tokio::task::AbortHandle {
+    let handle = tokio::spawn(async {
+        if elevation_tool() != "sudo" {
+            eprintln!("sudoloop has no effect with elevation_tool \"pkexec\"; skipping");
+            return;
+        }
+
+        // Validate credentials immediately so the loop starts from a fresh timestamp
+        let _ = run_command("sudo", &["-v"], false);
 
-    on_done(result);
-    Ok(())
+        let mut ticker = tokio::time::interval(SUDOLOOP_INTERVAL);
+        ticker.tick().await; // first tick fires immediately; skip it, we just validated above
+        loop {
+            ticker.tick().await;
+            let _ = run_command("sudo", &["-v"], false);
+        }
+    });
+    handle.abort_handle()
+}
+
+/// An event emitted while a command runs under [`run_command_streaming`]
+#[derive(Debug, Clone)]
+pub enum CommandEvent {
+    /// A raw line of output, stdout or stderr
+    Line(String),
+    /// A progress update parsed out of the output, if the command's format is recognized
+    Progress { percent: u8 },
+    /// The command has exited
+    Finished { status: CommandResult },
+}
+
+/// Parse a pacman-style `(12/48) upgrading foo` counter into a percentage
+fn parse_pacman_progress(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix('(')?;
+    let (counts, _) = rest.split_once(')')?;
+    let (done, total) = counts.split_once('/')?;
+    let done: u32 = done.trim().parse().ok()?;
+    let total: u32 = total.trim().parse().ok()?;
+    if total == 0 {
+        return None;
+    }
+    Some(((done * 100) / total).min(100) as u8)
+}
+
+/// Parse a flatpak-style percentage line (e.g. `Installing...  42%`) into a percentage
+fn parse_flatpak_progress(line: &str) -> Option<u8> {
+    let trimmed = line.trim();
+    let percent_str = trimmed.strip_suffix('%')?;
+    let percent_str = percent_str.rsplit(char::is_whitespace).next()?;
+    let percent: u8 = percent_str.parse().ok()?;
+    Some(percent.min(100))
+}
+
+/// Parse a single line of command output into a [`CommandEvent::Progress`], if recognized
+fn parse_progress_line(line: &str) -> Option<CommandEvent> {
+    parse_pacman_progress(line)
+        .or_else(|| parse_flatpak_progress(line))
+        .map(|percent| CommandEvent::Progress { percent })
+}
+
+/// Run a command asynchronously, streaming [`CommandEvent`]s as the process produces output.
+///
+/// Unlike [`run_command_async`], this returns immediately with a receiver the
+/// caller can poll or forward into a UI subscription, instead of blocking the
+/// caller until the process exits.
+pub async fn run_command_streaming(
+    cmd: &str,
+    args: &[&str],
+    use_pkexec: bool,
+) -> Result<mpsc::UnboundedReceiver<CommandEvent>, AppError> {
+    ShellCommand::new(cmd)
+        .args(args.iter().map(|s| s.to_string()))
+        .elevated(use_pkexec)
+        .run()
+        .await
 }
 
 #[cfg(test)]
@@ -142,4 +456,63 @@ mod tests {
         assert!(result.success());
         assert!(result.stdout.contains("hello"));
     }
+
+    #[test]
+    fn test_parse_pacman_progress() {
+        assert_eq!(parse_pacman_progress("(12/48) upgrading foo"), Some(25));
+        assert_eq!(parse_pacman_progress("(1/1) checking dependencies"), Some(100));
+        assert_eq!(parse_pacman_progress("not a progress line"), None);
+    }
+
+    #[test]
+    fn test_parse_flatpak_progress() {
+        assert_eq!(parse_flatpak_progress("Installing...  42%"), Some(42));
+        assert_eq!(parse_flatpak_progress("Downloading"), None);
+    }
+
+    #[test]
+    fn test_shell_command_builder() {
+        let result = ShellCommand::new("echo").arg("hello").wait().unwrap();
+        assert!(result.success());
+        assert!(result.stdout.contains("hello"));
+    }
+
+    #[test]
+    fn test_command_result_into_result() {
+        let ok = CommandResult { exit_code: 0, stdout: String::new(), stderr: String::new() };
+        assert!(ok.into_result().is_ok());
+
+        let failed = CommandResult {
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: "boom".to_string(),
+        };
+        match failed.into_result() {
+            Err(AppError::Command { code, stderr }) => {
+                assert_eq!(code, 1);
+                assert_eq!(stderr, "boom");
+            }
+            other => panic!("expected AppError::Command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_into_result_classifies_permission_denied() {
+        let dismissed = CommandResult { exit_code: 127, stdout: String::new(), stderr: String::new() };
+        assert!(matches!(dismissed.into_result(), Err(AppError::PermissionDenied(_))));
+
+        let bad_password = CommandResult {
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: "sudo: 3 incorrect password attempts".to_string(),
+        };
+        assert!(matches!(bad_password.into_result(), Err(AppError::PermissionDenied(_))));
+
+        let unrelated_failure = CommandResult {
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: "package not found".to_string(),
+        };
+        assert!(matches!(unrelated_failure.into_result(), Err(AppError::Command { .. })));
+    }
 }
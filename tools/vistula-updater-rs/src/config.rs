@@ -7,6 +7,33 @@ pub struct AppConfig {
     pub language: String,
     pub theme: String,
     pub categories: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub sudoloop_enabled: bool,
+    /// Which tool to use for privilege escalation: `"pkexec"` or `"sudo"`
+    #[serde(default = "default_elevation_tool")]
+    pub elevation_tool: String,
+    /// Whether to scan for `.pacnew`/`.pacsave` files and prompt to merge them
+    /// after a system update
+    #[serde(default = "default_pacdiff_check_enabled")]
+    pub pacdiff_check_enabled: bool,
+    /// Merge tool launched (elevated) on a chosen `.pacnew`/`.pacsave` entry
+    #[serde(default = "default_pacdiff_merge_tool")]
+    pub pacdiff_merge_tool: String,
+}
+
+fn default_elevation_tool() -> String {
+    "pkexec".to_string()
+}
+
+fn default_pacdiff_check_enabled() -> bool {
+    true
+}
+
+fn default_pacdiff_merge_tool() -> String {
+    // The updater is a GUI app with no controlling terminal to attach an
+    // interactive terminal-based tool (vimdiff, etc.) to, so default to a
+    // merge tool that opens its own window.
+    "meld".to_string()
 }
 
 impl Default for AppConfig {
@@ -15,12 +42,16 @@ impl Default for AppConfig {
             language: "en".to_string(),
             theme: "auto".to_string(),
             categories: HashMap::new(),
+            sudoloop_enabled: false,
+            elevation_tool: default_elevation_tool(),
+            pacdiff_check_enabled: default_pacdiff_check_enabled(),
+            pacdiff_merge_tool: default_pacdiff_merge_tool(),
         }
     }
 }
 
 /// Get config directory path (~/.config/vistula-updater)
-fn get_config_dir() -> PathBuf {
+pub fn config_dir() -> PathBuf {
     if let Ok(home) = std::env::var("HOME") {
         PathBuf::from(home).join(".config").join("vistula-updater")
     } else {
@@ -30,11 +61,15 @@ fn get_config_dir() -> PathBuf {
 
 /// Load configuration from file
 pub fn load_config() -> anyhow::Result<AppConfig> {
-    let config_dir = get_config_dir();
+    let config_dir = config_dir();
     let config_file = config_dir.join("config.json");
 
     if !config_file.exists() {
-        return Ok(AppConfig::default());
+        // First run: honor the environment-detected language. Once the user
+        // picks one explicitly it's saved here and takes priority from then on.
+        let mut config = AppConfig::default();
+        config.language = crate::i18n::detect_language();
+        return Ok(config);
     }
 
     let content = std::fs::read_to_string(&config_file)?;
@@ -44,7 +79,7 @@ pub fn load_config() -> anyhow::Result<AppConfig> {
 
 /// Save configuration to file
 pub fn save_config(config: &AppConfig) -> anyhow::Result<()> {
-    let config_dir = get_config_dir();
+    let config_dir = config_dir();
     std::fs::create_dir_all(&config_dir)?;
 
     let config_file = config_dir.join("config.json");
@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// A structured failure from anywhere in the update pipeline, kept `Clone` so
+/// it can flow through `iced::Message` variants and be matched on by the UI
+/// instead of just displayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// A local I/O failure not covered by a more specific variant below
+    Io(String),
+    /// A command exited with a non-zero status
+    Command { code: i32, stderr: String },
+    /// A network request failed (DNS, connection, timeout, bad response, ...)
+    Network(String),
+    /// A required file, binary, or resource doesn't exist
+    NotFound(String),
+    /// An operation was denied for lack of privileges
+    PermissionDenied(String),
+    /// Anything else, carried as a human-readable message
+    Other(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Io(msg) => write!(f, "I/O error: {}", msg),
+            AppError::Command { code, stderr } => {
+                write!(f, "command exited with status {}: {}", code, stderr)
+            }
+            AppError::Network(msg) => write!(f, "network error: {}", msg),
+            AppError::NotFound(msg) => write!(f, "not found: {}", msg),
+            AppError::PermissionDenied(msg) => write!(f, "permission denied: {}", msg),
+            AppError::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(e.to_string()),
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(e.to_string()),
+            _ => AppError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        AppError::Other(e.to_string())
+    }
+}
+
+/// The process exit code to use for a given [`AppError`], so callers like the
+/// notifier binary can report something more specific than "it failed"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppExitCode {
+    Ok = 0,
+    Io = 1,
+    Command = 2,
+    Network = 3,
+    NotFound = 4,
+    PermissionDenied = 5,
+    Other = 6,
+}
+
+impl AppExitCode {
+    pub fn from_error(err: &AppError) -> Self {
+        match err {
+            AppError::Io(_) => AppExitCode::Io,
+            AppError::Command { .. } => AppExitCode::Command,
+            AppError::Network(_) => AppExitCode::Network,
+            AppError::NotFound(_) => AppExitCode::NotFound,
+            AppError::PermissionDenied(_) => AppExitCode::PermissionDenied,
+            AppError::Other(_) => AppExitCode::Other,
+        }
+    }
+
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_mapping() {
+        assert_eq!(AppExitCode::from_error(&AppError::Network("timeout".into())).code(), 3);
+        assert_eq!(
+            AppExitCode::from_error(&AppError::Command { code: 1, stderr: String::new() }).code(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_io_error_classification() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert_eq!(AppError::from(io_err), AppError::NotFound("missing".to_string()));
+
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(AppError::from(io_err), AppError::PermissionDenied("denied".to_string()));
+    }
+}
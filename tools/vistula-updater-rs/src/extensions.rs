@@ -0,0 +1,194 @@
+use crate::backend::Backend;
+use crate::pacman::{PackageUpdate, UpdateSource};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use wasmtime::{Engine, Instance, Linker, Memory, Module, Store};
+
+/// Host-side state shared with a single WASM extension instance.
+///
+/// Extensions call back into `host_run_command` to shell out, and into
+/// `host_report_update` once per package they want to surface, rather than
+/// trying to marshal a `Vec<PackageUpdate>` across the WASM boundary.
+struct ExtensionState {
+    reported: Arc<Mutex<Vec<PackageUpdate>>>,
+}
+
+/// Directory extensions are loaded from: `~/.config/vistula-updater/extensions/*.wasm`
+fn extensions_dir() -> PathBuf {
+    crate::config::config_dir().join("extensions")
+}
+
+/// A package-source backend backed by a loaded WASM module
+pub struct WasmExtension {
+    name: String,
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmExtension {
+    /// Compile a `.wasm` file into a loadable extension
+    fn load(path: &std::path::Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("extension")
+            .to_string();
+        let engine = Engine::default();
+        let module = Module::from_file(&engine, path)
+            .with_context(|| format!("failed to compile extension: {}", path.display()))?;
+        Ok(Self { name, engine, module })
+    }
+
+    /// Instantiate the module with the host functions extensions are allowed to call
+    fn instantiate(&self) -> Result<(Store<ExtensionState>, Instance, Memory)> {
+        let state = ExtensionState {
+            reported: Arc::new(Mutex::new(Vec::new())),
+        };
+        let mut store = Store::new(&self.engine, state);
+        let mut linker: Linker<ExtensionState> = Linker::new(&self.engine);
+        let extension_name = self.name.clone();
+
+        // host_run_command(cmd_ptr, cmd_len, args_ptr, args_len) -> i32 exit code
+        linker.func_wrap(
+            "env",
+            "host_run_command",
+            |mut caller: wasmtime::Caller<'_, ExtensionState>,
+             cmd_ptr: i32,
+             cmd_len: i32,
+             args_ptr: i32,
+             args_len: i32|
+             -> i32 {
+                let memory = match caller.get_export("memory").and_then(|e| e.into_memory()) {
+                    Some(m) => m,
+                    None => return -1,
+                };
+                let cmd = match read_string(&mut caller, &memory, cmd_ptr, cmd_len) {
+                    Some(s) => s,
+                    None => return -1,
+                };
+                let args_raw = match read_string(&mut caller, &memory, args_ptr, args_len) {
+                    Some(s) => s,
+                    None => return -1,
+                };
+                let args: Vec<&str> = args_raw.split('\u{1f}').filter(|s| !s.is_empty()).collect();
+
+                match crate::commands::run_command(&cmd, &args, false) {
+                    Ok(result) => result.exit_code,
+                    Err(_) => -1,
+                }
+            },
+        )?;
+
+        // host_report_update(name_ptr, name_len, cur_ptr, cur_len, new_ptr, new_len)
+        linker.func_wrap(
+            "env",
+            "host_report_update",
+            move |mut caller: wasmtime::Caller<'_, ExtensionState>,
+             name_ptr: i32,
+             name_len: i32,
+             cur_ptr: i32,
+             cur_len: i32,
+             new_ptr: i32,
+             new_len: i32| {
+                let memory = match caller.get_export("memory").and_then(|e| e.into_memory()) {
+                    Some(m) => m,
+                    None => return,
+                };
+                let name = read_string(&mut caller, &memory, name_ptr, name_len);
+                let current_version = read_string(&mut caller, &memory, cur_ptr, cur_len);
+                let new_version = read_string(&mut caller, &memory, new_ptr, new_len);
+
+                if let (Some(name), Some(current_version), Some(new_version)) =
+                    (name, current_version, new_version)
+                {
+                    let reported = caller.data().reported.clone();
+                    reported.lock().unwrap().push(PackageUpdate {
+                        name,
+                        current_version,
+                        new_version,
+                        source: UpdateSource::Extension(extension_name.clone()),
+                    });
+                }
+            },
+        )?;
+
+        let instance = linker.instantiate(&mut store, &self.module)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .context("extension does not export a memory")?;
+        Ok((store, instance, memory))
+    }
+}
+
+fn read_string(
+    caller: &mut wasmtime::Caller<'_, ExtensionState>,
+    memory: &Memory,
+    ptr: i32,
+    len: i32,
+) -> Option<String> {
+    if ptr < 0 || len < 0 {
+        return None;
+    }
+    let mut buf = vec![0u8; len as usize];
+    memory.read(caller, ptr as usize, &mut buf).ok()?;
+    String::from_utf8(buf).ok()
+}
+
+#[async_trait]
+impl Backend for WasmExtension {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> anyhow::Result<Vec<PackageUpdate>> {
+        let (mut store, instance, _memory) = self.instantiate()?;
+        let check_fn = instance
+            .get_typed_func::<(), ()>(&mut store, "check")
+            .context("extension does not export `check`")?;
+        check_fn.call(&mut store, ())?;
+        let reported = store.data().reported.clone();
+        let updates = reported.lock().unwrap().clone();
+        Ok(updates)
+    }
+
+    async fn apply(&self, ids: &[String]) -> anyhow::Result<()> {
+        let (mut store, instance, memory) = self.instantiate()?;
+        let apply_fn = instance
+            .get_typed_func::<(i32, i32), ()>(&mut store, "apply")
+            .context("extension does not export `apply`")?;
+
+        let joined = ids.join("\u{1f}");
+        let bytes = joined.as_bytes();
+        // Grow memory by enough 64 KiB pages to hold the ids, then write them
+        // just past the end of the module's previous memory.
+        let offset = memory.data_size(&store);
+        memory.grow(&mut store, (bytes.len() as u64 / 65536) + 1)?;
+        memory.write(&mut store, offset, bytes)?;
+
+        apply_fn.call(&mut store, (offset as i32, bytes.len() as i32))?;
+        Ok(())
+    }
+}
+
+/// Scan the extensions directory and load every `.wasm` file found there
+pub fn load_extensions() -> Vec<Box<dyn Backend>> {
+    let dir = extensions_dir();
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "wasm").unwrap_or(false))
+        .filter_map(|path| match WasmExtension::load(&path) {
+            Ok(ext) => Some(Box::new(ext) as Box<dyn Backend>),
+            Err(e) => {
+                eprintln!("Failed to load extension {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
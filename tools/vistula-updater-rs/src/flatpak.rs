@@ -1,3 +1,5 @@
+use crate::commands::ShellCommand;
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,43 +40,157 @@ pub fn parse_flatpak_list(output: &str) -> Vec<FlatpakApp> {
 }
 
 /// Get installed flatpak applications
-pub async fn list_installed() -> Result<Vec<FlatpakApp>, String> {
-    let result = crate::commands::run_command(
-        "flatpak",
-        &["list", "--app", "--columns=application,name,origin"],
-        false,
-    ).map_err(|e| e.to_string())?;
+pub async fn list_installed() -> Result<Vec<FlatpakApp>, AppError> {
+    let result = ShellCommand::new("flatpak")
+        .args(["list", "--app", "--columns=application,name,origin"])
+        .wait()?;
     Ok(parse_flatpak_list(&result.stdout))
 }
 
-/// Search flatpak applications by query
-pub async fn search(query: &str, _remote: &str) -> Result<Vec<FlatpakApp>, String> {
-    let result = crate::commands::run_command(
-        "flatpak",
-        &["search", "--columns=id,name,default-branch", query],
-        false,
-    ).map_err(|e| e.to_string())?;
-    Ok(parse_flatpak_list(&result.stdout))
+/// Search flatpak applications by query, optionally restricted to a single remote.
+///
+/// `flatpak search` itself always searches every configured remote, so a
+/// non-empty `remote` is applied as a client-side filter on the result's
+/// origin column.
+pub async fn search(query: &str, remote: &str) -> Result<Vec<FlatpakApp>, AppError> {
+    let result = ShellCommand::new("flatpak")
+        .args(["search", "--columns=id,name,origin", query])
+        .wait()?;
+
+    let apps = parse_flatpak_list(&result.stdout);
+    if remote.is_empty() {
+        Ok(apps)
+    } else {
+        Ok(apps.into_iter().filter(|app| app.origin == remote).collect())
+    }
+}
+
+/// Install a flatpak application from a specific remote
+pub async fn install_from(appid: &str, remote: &str) -> Result<(), AppError> {
+    ShellCommand::new("flatpak")
+        .args(["install", remote, appid])
+        .elevated(true)
+        .wait()?
+        .into_result()?;
+    Ok(())
+}
+
+/// Install a flatpak application, letting flatpak pick the remote
+pub async fn install(appid: &str) -> Result<(), AppError> {
+    ShellCommand::new("flatpak")
+        .args(["install", appid])
+        .elevated(true)
+        .wait()?
+        .into_result()?;
+    Ok(())
 }
 
-/// Install a flatpak application
-pub async fn install(appid: &str) -> Result<(), String> {
-    crate::commands::run_command("flatpak", &["install", appid], true)
-        .map_err(|e| e.to_string())?;
+/// List configured flatpak remotes
+pub async fn list_remotes() -> Result<Vec<FlatpakRemote>, AppError> {
+    let result = ShellCommand::new("flatpak")
+        .args(["remotes", "--columns=name,url"])
+        .wait()?;
+
+    Ok(result
+        .stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?.to_string();
+            let url = parts.next()?.to_string();
+            Some(FlatpakRemote { is_default: name == "flathub", name, url })
+        })
+        .collect())
+}
+
+/// Add a flatpak remote
+pub async fn add_remote(name: &str, url: &str) -> Result<(), AppError> {
+    ShellCommand::new("flatpak")
+        .args(["remote-add", "--if-not-exists", name, url])
+        .elevated(true)
+        .wait()?
+        .into_result()?;
     Ok(())
 }
 
+/// Remove a flatpak remote
+pub async fn remove_remote(name: &str) -> Result<(), AppError> {
+    ShellCommand::new("flatpak")
+        .args(["remote-delete", name])
+        .elevated(true)
+        .wait()?
+        .into_result()?;
+    Ok(())
+}
+
+/// An app's runtime/sdk/permissions, derived from its flatpak manifest metadata
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlatpakManifest {
+    pub runtime: String,
+    pub sdk: String,
+    pub finish_args: Vec<String>,
+    pub modules: Vec<String>,
+}
+
+/// Parse the key-value metadata format `flatpak info --show-metadata` prints.
+///
+/// This is the installed app's runtime metadata, which doesn't carry the
+/// build-time module list from the original manifest, so `modules` is always
+/// left empty.
+fn parse_manifest_metadata(output: &str) -> FlatpakManifest {
+    let mut manifest = FlatpakManifest::default();
+    let mut section = "";
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        match (section, key) {
+            ("[Application]", "runtime") => manifest.runtime = value.to_string(),
+            ("[Application]", "sdk") => manifest.sdk = value.to_string(),
+            _ if section == "[Context]" || section == "[Session Bus Policy]" => {
+                manifest.finish_args.push(format!("{}={}", key, value));
+            }
+            _ => {}
+        }
+    }
+
+    manifest
+}
+
+/// Fetch an installed app's runtime/sdk/permission metadata
+pub async fn fetch_manifest(appid: &str) -> Result<FlatpakManifest, AppError> {
+    let result = ShellCommand::new("flatpak")
+        .args(["info", "--show-metadata", appid])
+        .wait()?;
+    Ok(parse_manifest_metadata(&result.stdout))
+}
+
 /// Uninstall a flatpak application
-pub async fn uninstall(appid: &str) -> Result<(), String> {
-    crate::commands::run_command("flatpak", &["uninstall", appid], true)
-        .map_err(|e| e.to_string())?;
+pub async fn uninstall(appid: &str) -> Result<(), AppError> {
+    ShellCommand::new("flatpak")
+        .args(["uninstall", appid])
+        .elevated(true)
+        .wait()?
+        .into_result()?;
     Ok(())
 }
 
 /// Update all installed flatpak applications
-pub async fn update_all() -> Result<(), String> {
-    crate::commands::run_command("flatpak", &["update"], true)
-        .map_err(|e| e.to_string())?;
+pub async fn update_all() -> Result<(), AppError> {
+    ShellCommand::new("flatpak")
+        .arg("update")
+        .elevated(true)
+        .wait()?
+        .into_result()?;
     Ok(())
 }
 
@@ -112,4 +228,22 @@ mod tests {
         assert_eq!(app.appid, "org.test.App");
         assert_eq!(app.version, Some("1.0.0".to_string()));
     }
+
+    #[test]
+    fn test_parse_manifest_metadata() {
+        let output = "[Application]\n\
+                      name=org.test.App\n\
+                      runtime=org.freedesktop.Platform/x86_64/23.08\n\
+                      sdk=org.freedesktop.Sdk/x86_64/23.08\n\
+                      \n\
+                      [Context]\n\
+                      shared=network;ipc;\n\
+                      sockets=x11;wayland;\n";
+        let manifest = parse_manifest_metadata(output);
+
+        assert_eq!(manifest.runtime, "org.freedesktop.Platform/x86_64/23.08");
+        assert_eq!(manifest.sdk, "org.freedesktop.Sdk/x86_64/23.08");
+        assert!(manifest.finish_args.contains(&"shared=network;ipc;".to_string()));
+        assert!(manifest.finish_args.contains(&"sockets=x11;wayland;".to_string()));
+    }
 }
@@ -5,13 +5,46 @@ use std::env;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
-type Translations = HashMap<String, String>;
+/// A single translation entry: either a plain string, or a CLDR-style plural
+/// object (`{"one": "...", "few": "...", "many": "...", "other": "..."}`)
+#[derive(Debug, Clone)]
+enum TranslationEntry {
+    Plain(String),
+    Plural(HashMap<String, String>),
+}
+
+type Translations = HashMap<String, TranslationEntry>;
 
 static LANGUAGE: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(detect_language()));
 static CACHE: Lazy<Mutex<HashMap<String, Translations>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// Languages always recognized, even when the assets directory can't be scanned
+const BUILTIN_LANGUAGES: &[&str] = &["en", "pl"];
+
+/// Scan `assets/i18n` for `*.json` files to discover which languages are installed,
+/// in addition to the languages VistulaOS always ships with.
+pub fn available_languages() -> Vec<String> {
+    let mut langs: Vec<String> = BUILTIN_LANGUAGES.iter().map(|s| s.to_string()).collect();
+
+    let i18n_dir = assets_path().join("i18n");
+    if let Ok(entries) = std::fs::read_dir(&i18n_dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    if !langs.iter().any(|l| l == stem) {
+                        langs.push(stem.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    langs
+}
+
 /// Detect system language from environment variables
-fn detect_language() -> String {
+pub fn detect_language() -> String {
     // Check for explicit override
     if let Ok(lang) = env::var("VISTULA_LANG") {
         return normalize_lang(&lang);
@@ -39,7 +72,7 @@ fn normalize_lang(lang: &str) -> String {
     let lang = lang.split('@').next().unwrap_or(lang);
     let lang = lang.split('_').next().unwrap_or(lang);
 
-    if lang == "pl" || lang == "en" {
+    if available_languages().iter().any(|l| l == lang) {
         lang.to_string()
     } else {
         String::from("en")
@@ -53,8 +86,8 @@ fn load_translations(lang: &str) -> anyhow::Result<Translations> {
         return Ok(trans.clone());
     }
 
-    let assets_path = get_assets_path();
-    let file_path = assets_path.join("i18n").join(format!("{}.json", lang));
+    let path = assets_path();
+    let file_path = path.join("i18n").join(format!("{}.json", lang));
 
     let content = std::fs::read_to_string(&file_path)
         .unwrap_or_else(|_| String::from("{}"));
@@ -64,8 +97,18 @@ fn load_translations(lang: &str) -> anyhow::Result<Translations> {
 
     let mut translations = Translations::new();
     for (key, value) in json {
-        if let Some(text) = value.as_str() {
-            translations.insert(key, text.to_string());
+        match &value {
+            Value::String(text) => {
+                translations.insert(key, TranslationEntry::Plain(text.clone()));
+            }
+            Value::Object(forms) => {
+                let forms: HashMap<String, String> = forms
+                    .iter()
+                    .filter_map(|(form, text)| text.as_str().map(|t| (form.clone(), t.to_string())))
+                    .collect();
+                translations.insert(key, TranslationEntry::Plural(forms));
+            }
+            _ => {}
         }
     }
 
@@ -74,7 +117,7 @@ fn load_translations(lang: &str) -> anyhow::Result<Translations> {
 }
 
 /// Get assets directory path
-fn get_assets_path() -> PathBuf {
+pub fn assets_path() -> PathBuf {
     // Override via environment variable
     if let Ok(path) = env::var("VISTULA_UPDATER_ASSETS") {
         return PathBuf::from(path);
@@ -117,28 +160,84 @@ pub fn t(key: &str) -> String {
 /// Translate with argument substitution
 pub fn t_with_args(key: &str, args: &HashMap<&str, String>) -> String {
     let lang = current_language();
-    let text = load_translations(&lang)
-        .ok()
-        .and_then(|trans| trans.get(key).cloned())
-        .or_else(|| {
-            load_translations("en")
-                .ok()
-                .and_then(|trans| trans.get(key).cloned())
-        })
+    let text = plain_entry(&lang, key)
+        .or_else(|| plain_entry("en", key))
         .unwrap_or_else(|| key.to_string());
 
-    if args.is_empty() {
-        text
-    } else {
-        // Simple string substitution: {key} -> value
-        let mut result = text;
-        for (k, v) in args {
-            result = result.replace(&format!("{{{}}}", k), v);
+    substitute(text, args)
+}
+
+/// Look up a `Plain` translation entry for a key, in the given language only
+fn plain_entry(lang: &str, key: &str) -> Option<String> {
+    load_translations(lang).ok().and_then(|trans| match trans.get(key) {
+        Some(TranslationEntry::Plain(text)) => Some(text.clone()),
+        _ => None,
+    })
+}
+
+/// Look up a `Plural` translation entry's forms for a key, in the given language only
+fn plural_entry(lang: &str, key: &str) -> Option<HashMap<String, String>> {
+    load_translations(lang).ok().and_then(|trans| match trans.get(key) {
+        Some(TranslationEntry::Plural(forms)) => Some(forms.clone()),
+        _ => None,
+    })
+}
+
+/// Simple `{key}` -> value substitution
+fn substitute(mut text: String, args: &HashMap<&str, String>) -> String {
+    for (k, v) in args {
+        text = text.replace(&format!("{{{}}}", k), v);
+    }
+    text
+}
+
+/// Select the CLDR plural category for `count` in `lang`.
+///
+/// Only the categories actually needed by the shipped languages are
+/// implemented: English distinguishes `one`/`other`; Polish distinguishes
+/// `one`/`few`/`many` per the standard CLDR rule.
+fn plural_category(lang: &str, count: i64) -> &'static str {
+    let count = count.unsigned_abs();
+    match lang {
+        "pl" => {
+            if count == 1 {
+                "one"
+            } else {
+                let mod10 = count % 10;
+                let mod100 = count % 100;
+                if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                    "few"
+                } else {
+                    "many"
+                }
+            }
+        }
+        _ => {
+            if count == 1 {
+                "one"
+            } else {
+                "other"
+            }
         }
-        result
     }
 }
 
+/// Translate a pluralized message key, selecting the form for `count` per CLDR
+/// rules and auto-injecting `count` into the substitution arguments.
+pub fn t_plural(key: &str, count: i64, args: &HashMap<&str, String>) -> String {
+    let lang = current_language();
+    let category = plural_category(&lang, count);
+
+    let forms = plural_entry(&lang, key).or_else(|| plural_entry("en", key));
+    let text = forms
+        .and_then(|forms| forms.get(category).or_else(|| forms.get("other")).cloned())
+        .unwrap_or_else(|| key.to_string());
+
+    let mut args = args.clone();
+    args.insert("count", count.to_string());
+    substitute(text, &args)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +251,13 @@ mod tests {
         assert_eq!(normalize_lang("de"), "en"); // fallback for unknown
     }
 
+    #[test]
+    fn test_available_languages_includes_builtins() {
+        let langs = available_languages();
+        assert!(langs.contains(&"en".to_string()));
+        assert!(langs.contains(&"pl".to_string()));
+    }
+
     #[test]
     fn test_translate() {
         set_language("en");
@@ -168,4 +274,23 @@ mod tests {
         let text = t_with_args("notify.updates_available", &args);
         assert!(text.contains("5"));
     }
+
+    #[test]
+    fn test_plural_category_english() {
+        assert_eq!(plural_category("en", 1), "one");
+        assert_eq!(plural_category("en", 0), "other");
+        assert_eq!(plural_category("en", 2), "other");
+        assert_eq!(plural_category("en", 5), "other");
+    }
+
+    #[test]
+    fn test_plural_category_polish() {
+        assert_eq!(plural_category("pl", 1), "one");
+        assert_eq!(plural_category("pl", 2), "few");
+        assert_eq!(plural_category("pl", 4), "few");
+        assert_eq!(plural_category("pl", 5), "many");
+        assert_eq!(plural_category("pl", 12), "many");
+        assert_eq!(plural_category("pl", 22), "few");
+        assert_eq!(plural_category("pl", 0), "many");
+    }
 }
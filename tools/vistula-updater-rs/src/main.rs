@@ -1,15 +1,142 @@
+mod aur;
+mod backend;
 mod cinnamon;
 mod commands;
 mod config;
+mod error;
+mod extensions;
 mod i18n;
 mod pacman;
 mod flatpak;
 
+use error::AppError;
 use iced::{
-    executor, widget::{column, container, row, text, button, text_input, scrollable},
-    Element, Settings, Application, Command, Length, Alignment,
+    executor, subscription,
+    widget::{
+        column, container, pick_list, progress_bar, row, text, button, text_input, scrollable, toggler,
+    },
+    Element, Settings, Application, Command, Length, Alignment, Subscription,
 };
 use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Cap on the number of lines kept in a streamed operation's log pane
+const MAX_LOG_LINES: usize = 500;
+
+/// Identifies which long-running, streamed operation is currently active
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum StreamKind {
+    SystemUpdate,
+    FlatpakInstall(String),
+    AurInstall(String),
+}
+
+/// Internal state threaded through a streamed operation's subscription
+enum StreamState {
+    Starting { sudoloop_enabled: bool },
+    Running {
+        rx: tokio::sync::mpsc::UnboundedReceiver<commands::CommandEvent>,
+        keepalive: Option<tokio::task::AbortHandle>,
+    },
+    Done,
+}
+
+/// Start the command backing a given [`StreamKind`], returning its event
+/// stream. AUR installs are a two-command pipeline (`git` then `makepkg`)
+/// assembled by [`aur::install_streaming`], so this can't be expressed as a
+/// single [`commands::ShellCommand`] the way the other kinds are.
+async fn start_stream(kind: &StreamKind) -> Result<tokio::sync::mpsc::UnboundedReceiver<commands::CommandEvent>, AppError> {
+    match kind {
+        StreamKind::SystemUpdate => commands::ShellCommand::new("pacman").arg("-Syu").elevated(true).run().await,
+        StreamKind::FlatpakInstall(appid) => {
+            commands::ShellCommand::new("flatpak")
+                .args(["install", appid.as_str()])
+                .elevated(true)
+                .run()
+                .await
+        }
+        StreamKind::AurInstall(pkg) => aur::install_streaming(pkg).await,
+    }
+}
+
+/// The message a [`StreamKind`] emits once its command has exited.
+///
+/// `SystemUpdate` carries the still-running keepalive handle (if any) rather
+/// than having it aborted here, since a system update's AUR/extension
+/// catch-up runs after the stream closes and benefits from the same
+/// keepalive; the other kinds have no such follow-up phase, so theirs is
+/// aborted immediately.
+fn stream_finished_message(
+    kind: &StreamKind,
+    result: Result<(), AppError>,
+    keepalive: Option<tokio::task::AbortHandle>,
+) -> Message {
+    match kind {
+        StreamKind::SystemUpdate => Message::SystemUpdated(result, keepalive),
+        StreamKind::FlatpakInstall(_) => {
+            if let Some(handle) = keepalive {
+                handle.abort();
+            }
+            Message::FlatpakInstalled(result)
+        }
+        StreamKind::AurInstall(_) => {
+            if let Some(handle) = keepalive {
+                handle.abort();
+            }
+            Message::AurInstalled(result)
+        }
+    }
+}
+
+/// Advance a streamed operation by one [`commands::CommandEvent`]
+async fn advance_stream(
+    kind: StreamKind,
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<commands::CommandEvent>,
+    keepalive: Option<tokio::task::AbortHandle>,
+) -> (Message, StreamState) {
+    match rx.recv().await {
+        Some(commands::CommandEvent::Line(line)) => {
+            (Message::OutputLine(kind, line), StreamState::Running { rx, keepalive })
+        }
+        Some(commands::CommandEvent::Progress { percent }) => (
+            Message::StreamProgress(kind, percent),
+            StreamState::Running { rx, keepalive },
+        ),
+        Some(commands::CommandEvent::Finished { status }) => {
+            let result = status.into_result().map(|_| ());
+            (stream_finished_message(&kind, result, keepalive), StreamState::Done)
+        }
+        None => {
+            let result = Err(AppError::Other("command stream closed unexpectedly".to_string()));
+            (stream_finished_message(&kind, result, keepalive), StreamState::Done)
+        }
+    }
+}
+
+/// Subscribe to a streamed operation's output, emitting `OutputLine`/`StreamProgress`
+/// per event and a final `SystemUpdated`/`FlatpakInstalled`/`AurInstalled` once it completes.
+fn stream_subscription(kind: StreamKind, sudoloop_enabled: bool) -> Subscription<Message> {
+    subscription::unfold(kind.clone(), StreamState::Starting { sudoloop_enabled }, move |state| {
+        let kind = kind.clone();
+        async move {
+            match state {
+                StreamState::Starting { sudoloop_enabled } => {
+                    let keepalive = if sudoloop_enabled {
+                        Some(commands::start_sudoloop().await)
+                    } else {
+                        None
+                    };
+                    match start_stream(&kind).await {
+                        Ok(rx) => advance_stream(kind, rx, keepalive).await,
+                        Err(e) => (stream_finished_message(&kind, Err(e), keepalive), StreamState::Done),
+                    }
+                }
+                StreamState::Running { rx, keepalive } => advance_stream(kind, rx, keepalive).await,
+                StreamState::Done => std::future::pending().await,
+            }
+        }
+    })
+}
 
 pub fn main() -> iced::Result {
     VistulaUpdater::run(Settings::default())
@@ -19,6 +146,7 @@ pub fn main() -> iced::Result {
 enum Tab {
     System,
     Flatpak,
+    Aur,
     Settings,
 }
 
@@ -27,38 +155,89 @@ enum Message {
     TabChanged(Tab),
     // System tab
     CheckUpdates,
-    UpdatesChecked(Result<Vec<pacman::PackageUpdate>, String>),
+    UpdatesChecked(Result<Vec<pacman::PackageUpdate>, AppError>),
     UpdateSystem,
-    SystemUpdated(Result<(), String>),
+    SystemUpdated(Result<(), AppError>, Option<tokio::task::AbortHandle>),
+    AurUpdatesApplied(Result<(), AppError>),
+    ExtensionUpdatesApplied(Result<(), AppError>),
     // Flatpak tab
     SearchFlatpaks,
     SearchQueryChanged(String),
-    FlatpaksFound(Result<Vec<flatpak::FlatpakApp>, String>),
+    FlatpaksFound(Result<Vec<flatpak::FlatpakApp>, AppError>),
     InstallFlatpak(String),
-    FlatpakInstalled(Result<(), String>),
+    FlatpakInstalled(Result<(), AppError>),
+    // Streamed operation output (System/Flatpak/AUR)
+    OutputLine(StreamKind, String),
+    StreamProgress(StreamKind, u8),
+    // Post-update .pacnew/.pacsave handling
+    PacdiffScanned(Result<Vec<pacman::PacdiffEntry>, AppError>),
+    MergePacdiff(usize),
+    PacdiffMerged(String, Result<(), AppError>),
     ListInstalledFlatpaks,
-    InstalledLoaded(Result<Vec<flatpak::FlatpakApp>, String>),
+    InstalledLoaded(Result<Vec<flatpak::FlatpakApp>, AppError>),
+    ShowManifest(String),
+    ManifestFetched(Result<(String, flatpak::FlatpakManifest), AppError>),
+    // AUR tab
+    SearchAur,
+    AurSearchQueryChanged(String),
+    AurFound(Result<Vec<aur::AurPackage>, AppError>),
+    InstallAur(String),
+    AurInstalled(Result<(), AppError>),
     // Settings tab
     LanguageChanged(String),
     ThemeChanged(String),
+    SudoloopToggled(bool),
+    PacdiffCheckToggled(bool),
 }
 
 struct VistulaUpdater {
     current_tab: Tab,
     config: config::AppConfig,
-    
+    backend_registry: Arc<backend::BackendRegistry>,
+    active_stream: Option<StreamKind>,
+
     // System tab state
     available_updates: Vec<pacman::PackageUpdate>,
     checking_updates: bool,
     system_status: String,
-    
+    system_error: Option<AppError>,
+    system_log: Vec<String>,
+    system_progress: Option<u8>,
+    pending_pacdiff: Vec<pacman::PacdiffEntry>,
+    /// AUR-sourced package names awaiting catch-up once the in-flight
+    /// `pacman -Syu` (which can't touch them) finishes
+    pending_aur_updates: Vec<String>,
+    /// Extension-sourced package names awaiting catch-up, grouped by the
+    /// backend name that reported them
+    pending_extension_updates: Vec<(String, Vec<String>)>,
+    /// The system update's keepalive, kept alive across the AUR/extension
+    /// catch-up that follows it, and the number of catch-up commands still
+    /// outstanding before it should be aborted
+    sudoloop_handle: Option<tokio::task::AbortHandle>,
+    pending_catchups: usize,
+
     // Flatpak tab state
     flatpak_search_query: String,
     flatpak_search_results: Vec<flatpak::FlatpakApp>,
     installed_flatpaks: Vec<flatpak::FlatpakApp>,
     searching_flatpaks: bool,
     flatpak_status: String,
-    
+    flatpak_error: Option<AppError>,
+    flatpak_log: Vec<String>,
+    flatpak_progress: Option<u8>,
+    /// Runtime/sdk/permission metadata for the last app a "Details" button
+    /// was pressed for
+    flatpak_manifest: Option<(String, flatpak::FlatpakManifest)>,
+
+    // AUR tab state
+    aur_search_query: String,
+    aur_search_results: Vec<aur::AurPackage>,
+    searching_aur: bool,
+    aur_status: String,
+    aur_error: Option<AppError>,
+    aur_log: Vec<String>,
+    aur_progress: Option<u8>,
+
     // Settings state
     available_languages: Vec<String>,
 }
@@ -73,20 +252,46 @@ impl iced::Application for VistulaUpdater {
         let config = config::load_config().unwrap_or_default();
         let lang = config.language.clone();
         i18n::set_language(&lang);
-        
+
+        let mut registry = backend::BackendRegistry::with_builtins();
+        for extension in extensions::load_extensions() {
+            registry.register(extension);
+        }
+
         (
             VistulaUpdater {
                 current_tab: Tab::System,
                 config,
+                backend_registry: Arc::new(registry),
+                active_stream: None,
                 available_updates: Vec::new(),
                 checking_updates: false,
                 system_status: i18n::t("sys.check"),
+                system_error: None,
+                system_log: Vec::new(),
+                system_progress: None,
+                pending_pacdiff: Vec::new(),
+                pending_aur_updates: Vec::new(),
+                pending_extension_updates: Vec::new(),
+                sudoloop_handle: None,
+                pending_catchups: 0,
                 flatpak_search_query: String::new(),
                 flatpak_search_results: Vec::new(),
                 installed_flatpaks: Vec::new(),
                 searching_flatpaks: false,
                 flatpak_status: String::new(),
-                available_languages: vec!["en".to_string(), "pl".to_string()],
+                flatpak_error: None,
+                flatpak_log: Vec::new(),
+                flatpak_progress: None,
+                flatpak_manifest: None,
+                aur_search_query: String::new(),
+                aur_search_results: Vec::new(),
+                searching_aur: false,
+                aur_status: String::new(),
+                aur_error: None,
+                aur_log: Vec::new(),
+                aur_progress: None,
+                available_languages: i18n::available_languages(),
             },
             Command::none(),
         )
@@ -96,6 +301,13 @@ impl iced::Application for VistulaUpdater {
         i18n::t("app.title")
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        match &self.active_stream {
+            Some(kind) => stream_subscription(kind.clone(), self.config.sudoloop_enabled),
+            None => Subscription::none(),
+        }
+    }
+
     fn update(&mut self, message: Message) -> Command<Self::Message> {
         match message {
             Message::TabChanged(tab) => {
@@ -104,12 +316,17 @@ impl iced::Application for VistulaUpdater {
                     Tab::System => {
                         self.system_status = i18n::t("sys.status.checking");
                         self.checking_updates = true;
-                        Command::perform(pacman::check_for_updates(), Message::UpdatesChecked)
+                        let registry = self.backend_registry.clone();
+                        Command::perform(
+                            async move { backend::check_all_flattened(&registry).await },
+                            Message::UpdatesChecked,
+                        )
                     }
                     Tab::Flatpak => {
                         self.flatpak_status = i18n::t("fp.status.loading_installed");
                         Command::perform(flatpak::list_installed(), Message::InstalledLoaded)
                     }
+                    Tab::Aur => Command::none(),
                     Tab::Settings => Command::none(),
                 }
             }
@@ -118,13 +335,18 @@ impl iced::Application for VistulaUpdater {
             Message::CheckUpdates => {
                 self.checking_updates = true;
                 self.system_status = i18n::t("sys.status.checking");
-                Command::perform(pacman::check_for_updates(), Message::UpdatesChecked)
+                let registry = self.backend_registry.clone();
+                Command::perform(
+                    async move { backend::check_all_flattened(&registry).await },
+                    Message::UpdatesChecked,
+                )
             }
             
             Message::UpdatesChecked(result) => {
                 self.checking_updates = false;
                 match result {
                     Ok(updates) => {
+                        self.system_error = None;
                         let count = updates.len();
                         self.available_updates = updates;
                         if count > 0 {
@@ -137,6 +359,7 @@ impl iced::Application for VistulaUpdater {
                     }
                     Err(e) => {
                         self.system_status = format!("{}: {}", i18n::t("sys.status.check_error"), e);
+                        self.system_error = Some(e);
                     }
                 }
                 Command::none()
@@ -145,18 +368,206 @@ impl iced::Application for VistulaUpdater {
             Message::UpdateSystem => {
                 self.system_status = i18n::t("sys.status.updating");
                 self.checking_updates = true;
-                Command::perform(pacman::update_system(), Message::SystemUpdated)
+                self.system_log.clear();
+                self.system_progress = None;
+                // `pacman -Syu` below only ever touches repo packages, so
+                // capture which of the currently-listed updates are
+                // AUR-sourced now, while `available_updates` still reflects
+                // them, to catch them up separately once it finishes.
+                self.pending_aur_updates = self
+                    .available_updates
+                    .iter()
+                    .filter(|u| u.source == pacman::UpdateSource::Aur)
+                    .map(|u| u.name.clone())
+                    .collect();
+                // Likewise, extension-sourced updates aren't touched by
+                // `-Syu` either; group them by the backend that reported
+                // them so each can be caught up through its own `apply`.
+                self.pending_extension_updates = Vec::new();
+                for update in &self.available_updates {
+                    if let pacman::UpdateSource::Extension(backend_name) = &update.source {
+                        if let Some(entry) = self
+                            .pending_extension_updates
+                            .iter_mut()
+                            .find(|entry| &entry.0 == backend_name)
+                        {
+                            entry.1.push(update.name.clone());
+                        } else {
+                            self.pending_extension_updates
+                                .push((backend_name.clone(), vec![update.name.clone()]));
+                        }
+                    }
+                }
+                self.active_stream = Some(StreamKind::SystemUpdate);
+                Command::none()
             }
-            
-            Message::SystemUpdated(result) => {
+
+            Message::SystemUpdated(result, keepalive) => {
                 self.checking_updates = false;
+                self.active_stream = None;
+                self.system_progress = None;
+                let mut should_scan_pacdiff = false;
+                let mut aur_ids = Vec::new();
+                let mut extension_ids = Vec::new();
                 match result {
                     Ok(_) => {
+                        self.system_error = None;
                         self.system_status = i18n::t("sys.status.updated");
                         self.available_updates.clear();
+                        should_scan_pacdiff = self.config.pacdiff_check_enabled;
+                        aur_ids = std::mem::take(&mut self.pending_aur_updates);
+                        extension_ids = std::mem::take(&mut self.pending_extension_updates);
                     }
                     Err(e) => {
                         self.system_status = format!("{}: {}", i18n::t("sys.status.update_failed"), e);
+                        self.system_error = Some(e);
+                    }
+                }
+
+                let mut commands = Vec::new();
+                if should_scan_pacdiff {
+                    commands.push(Command::perform(async { pacman::scan_pacdiff() }, Message::PacdiffScanned));
+                }
+                if !aur_ids.is_empty() {
+                    let registry = self.backend_registry.clone();
+                    commands.push(Command::perform(
+                        async move {
+                            registry
+                                .apply_to("pacman", &aur_ids)
+                                .await
+                                .map_err(|e| AppError::Other(e.to_string()))
+                        },
+                        Message::AurUpdatesApplied,
+                    ));
+                }
+                for (backend_name, ids) in extension_ids {
+                    let registry = self.backend_registry.clone();
+                    commands.push(Command::perform(
+                        async move {
+                            registry
+                                .apply_to(&backend_name, &ids)
+                                .await
+                                .map_err(|e| AppError::Other(e.to_string()))
+                        },
+                        Message::ExtensionUpdatesApplied,
+                    ));
+                }
+
+                // AUR/extension builds can run long, so keep the keepalive
+                // from the just-finished `pacman -Syu` stream alive across
+                // them rather than letting it end the moment that stream
+                // closes; abort it immediately if there's nothing left for
+                // it to cover.
+                let catchup_count = commands.len() - (should_scan_pacdiff as usize);
+                if catchup_count > 0 {
+                    self.pending_catchups = catchup_count;
+                    self.sudoloop_handle = keepalive;
+                } else if let Some(handle) = keepalive {
+                    handle.abort();
+                }
+                Command::batch(commands)
+            }
+
+            // Catch up the AUR-sourced packages `pacman -Syu` can't touch,
+            // then re-check so the System tab's list reflects reality.
+            Message::AurUpdatesApplied(result) => {
+                self.pending_catchups = self.pending_catchups.saturating_sub(1);
+                if self.pending_catchups == 0 {
+                    if let Some(handle) = self.sudoloop_handle.take() {
+                        handle.abort();
+                    }
+                }
+                match result {
+                    Ok(_) => {
+                        self.system_error = None;
+                        let registry = self.backend_registry.clone();
+                        Command::perform(
+                            async move { backend::check_all_flattened(&registry).await },
+                            Message::UpdatesChecked,
+                        )
+                    }
+                    Err(e) => {
+                        self.system_status = format!("{}: {}", i18n::t("sys.status.aur_update_failed"), e);
+                        self.system_error = Some(e);
+                        Command::none()
+                    }
+                }
+            }
+
+            // Catch up the extension-sourced packages `pacman -Syu` can't
+            // touch, then re-check so the System tab's list reflects reality.
+            Message::ExtensionUpdatesApplied(result) => {
+                self.pending_catchups = self.pending_catchups.saturating_sub(1);
+                if self.pending_catchups == 0 {
+                    if let Some(handle) = self.sudoloop_handle.take() {
+                        handle.abort();
+                    }
+                }
+                match result {
+                    Ok(_) => {
+                        self.system_error = None;
+                        let registry = self.backend_registry.clone();
+                        Command::perform(
+                            async move { backend::check_all_flattened(&registry).await },
+                            Message::UpdatesChecked,
+                        )
+                    }
+                    Err(e) => {
+                        self.system_status =
+                            format!("{}: {}", i18n::t("sys.status.extension_update_failed"), e);
+                        self.system_error = Some(e);
+                        Command::none()
+                    }
+                }
+            }
+
+            Message::PacdiffScanned(result) => {
+                match result {
+                    Ok(entries) => {
+                        self.system_error = None;
+                        self.pending_pacdiff = entries;
+                    }
+                    Err(e) => {
+                        self.system_status = format!("{}: {}", i18n::t("sys.status.pacdiff_scan_error"), e);
+                        self.system_error = Some(e);
+                    }
+                }
+                Command::none()
+            }
+
+            Message::MergePacdiff(index) => {
+                if let Some(entry) = self.pending_pacdiff.get(index).cloned() {
+                    let merge_tool = self.config.pacdiff_merge_tool.clone();
+                    let path = entry.path.clone();
+                    Command::perform(
+                        async move {
+                            let result = tokio::task::spawn_blocking(move || {
+                                pacman::merge_pacdiff_entry(&entry, &merge_tool)
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(AppError::Other(e.to_string())));
+                            (path, result)
+                        },
+                        |(path, result)| Message::PacdiffMerged(path, result),
+                    )
+                } else {
+                    Command::none()
+                }
+            }
+
+            // Identified by `path` rather than the index captured at click
+            // time, since two merges can be in flight together and complete
+            // out of order, which would otherwise shift indices and drop the
+            // wrong entry from `pending_pacdiff`.
+            Message::PacdiffMerged(path, result) => {
+                match result {
+                    Ok(_) => {
+                        self.system_error = None;
+                        self.pending_pacdiff.retain(|entry| entry.path != path);
+                    }
+                    Err(e) => {
+                        self.system_status = format!("{}: {}", i18n::t("sys.status.pacdiff_merge_error"), e);
+                        self.system_error = Some(e);
                     }
                 }
                 Command::none()
@@ -188,6 +599,7 @@ impl iced::Application for VistulaUpdater {
                 self.searching_flatpaks = false;
                 match result {
                     Ok(apps) => {
+                        self.flatpak_error = None;
                         let count = apps.len();
                         self.flatpak_search_results = apps;
                         let mut args = HashMap::new();
@@ -196,6 +608,7 @@ impl iced::Application for VistulaUpdater {
                     }
                     Err(e) => {
                         self.flatpak_status = format!("{}: {}", i18n::t("fp.status.search_error"), e);
+                        self.flatpak_error = Some(e);
                     }
                 }
                 Command::none()
@@ -204,27 +617,51 @@ impl iced::Application for VistulaUpdater {
             Message::InstallFlatpak(appid) => {
                 self.flatpak_status = i18n::t("fp.status.installing");
                 self.searching_flatpaks = true;
-                let app_id = appid.clone();
-                Command::perform(
-                    async move {
-                        flatpak::install(&app_id).await
-                    },
-                    Message::FlatpakInstalled,
-                )
+                self.flatpak_log.clear();
+                self.flatpak_progress = None;
+                self.active_stream = Some(StreamKind::FlatpakInstall(appid));
+                Command::none()
             }
-            
+
             Message::FlatpakInstalled(result) => {
                 self.searching_flatpaks = false;
+                self.active_stream = None;
+                self.flatpak_progress = None;
                 match result {
                     Ok(_) => {
+                        self.flatpak_error = None;
                         self.flatpak_status = i18n::t("fp.status.installed");
                     }
                     Err(e) => {
                         self.flatpak_status = format!("{}: {}", i18n::t("fp.status.install_failed"), e);
+                        self.flatpak_error = Some(e);
                     }
                 }
                 Command::none()
             }
+
+            Message::OutputLine(kind, line) => {
+                let log = match kind {
+                    StreamKind::SystemUpdate => &mut self.system_log,
+                    StreamKind::FlatpakInstall(_) => &mut self.flatpak_log,
+                    StreamKind::AurInstall(_) => &mut self.aur_log,
+                };
+                log.push(line);
+                if log.len() > MAX_LOG_LINES {
+                    let overflow = log.len() - MAX_LOG_LINES;
+                    log.drain(0..overflow);
+                }
+                Command::none()
+            }
+
+            Message::StreamProgress(kind, percent) => {
+                match kind {
+                    StreamKind::SystemUpdate => self.system_progress = Some(percent),
+                    StreamKind::FlatpakInstall(_) => self.flatpak_progress = Some(percent),
+                    StreamKind::AurInstall(_) => self.aur_progress = Some(percent),
+                }
+                Command::none()
+            }
             
             Message::ListInstalledFlatpaks => {
                 self.flatpak_status = i18n::t("fp.status.loading_installed");
@@ -234,16 +671,102 @@ impl iced::Application for VistulaUpdater {
             Message::InstalledLoaded(result) => {
                 match result {
                     Ok(apps) => {
+                        self.flatpak_error = None;
                         self.installed_flatpaks = apps;
                         self.flatpak_status = String::new();
                     }
                     Err(e) => {
                         self.flatpak_status = format!("Error: {}", e);
+                        self.flatpak_error = Some(e);
                     }
                 }
                 Command::none()
             }
-            
+
+            Message::ShowManifest(appid) => Command::perform(
+                async move {
+                    let manifest = flatpak::fetch_manifest(&appid).await?;
+                    Ok((appid, manifest))
+                },
+                Message::ManifestFetched,
+            ),
+
+            Message::ManifestFetched(result) => {
+                match result {
+                    Ok((appid, manifest)) => {
+                        self.flatpak_error = None;
+                        self.flatpak_manifest = Some((appid, manifest));
+                    }
+                    Err(e) => {
+                        self.flatpak_status = format!("{}: {}", i18n::t("fp.status.manifest_error"), e);
+                        self.flatpak_error = Some(e);
+                    }
+                }
+                Command::none()
+            }
+
+            // AUR tab handlers
+            Message::AurSearchQueryChanged(query) => {
+                self.aur_search_query = query;
+                Command::none()
+            }
+
+            Message::SearchAur => {
+                if self.aur_search_query.is_empty() {
+                    self.aur_status = i18n::t("aur.status.type_query");
+                    return Command::none();
+                }
+                self.searching_aur = true;
+                self.aur_status = i18n::t("aur.status.searching");
+                let query = self.aur_search_query.clone();
+                Command::perform(async move { aur::search(&query).await }, Message::AurFound)
+            }
+
+            Message::AurFound(result) => {
+                self.searching_aur = false;
+                match result {
+                    Ok(packages) => {
+                        self.aur_error = None;
+                        let count = packages.len();
+                        self.aur_search_results = packages;
+                        let mut args = HashMap::new();
+                        args.insert("n", count.to_string());
+                        self.aur_status = i18n::t_with_args("aur.status.results", &args);
+                    }
+                    Err(e) => {
+                        self.aur_status = format!("{}: {}", i18n::t("aur.status.search_error"), e);
+                        self.aur_error = Some(e);
+                    }
+                }
+                Command::none()
+            }
+
+            Message::InstallAur(name) => {
+                self.aur_status = i18n::t("aur.status.installing");
+                self.searching_aur = true;
+                self.aur_log.clear();
+                self.aur_progress = None;
+                self.active_stream = Some(StreamKind::AurInstall(name));
+                Command::none()
+            }
+
+            Message::AurInstalled(result) => {
+                self.searching_aur = false;
+                self.active_stream = None;
+                self.aur_progress = None;
+                match result {
+                    Ok(_) => {
+                        self.aur_error = None;
+                        self.aur_status = i18n::t("aur.status.installed");
+                    }
+                    Err(e) => {
+                        self.aur_status = format!("{}: {}", i18n::t("aur.status.install_failed"), e);
+                        self.aur_error = Some(e);
+                    }
+                }
+                Command::none()
+            }
+
             // Settings handlers
             Message::LanguageChanged(lang) => {
                 self.config.language = lang;
@@ -257,6 +780,18 @@ impl iced::Application for VistulaUpdater {
                 let _ = config::save_config(&self.config);
                 Command::none()
             }
+
+            Message::SudoloopToggled(enabled) => {
+                self.config.sudoloop_enabled = enabled;
+                let _ = config::save_config(&self.config);
+                Command::none()
+            }
+
+            Message::PacdiffCheckToggled(enabled) => {
+                self.config.pacdiff_check_enabled = enabled;
+                let _ = config::save_config(&self.config);
+                Command::none()
+            }
         }
     }
 
@@ -266,6 +801,8 @@ impl iced::Application for VistulaUpdater {
                 .on_press(Message::TabChanged(Tab::System)),
             button(text(i18n::t("tab.flatpak")))
                 .on_press(Message::TabChanged(Tab::Flatpak)),
+            button(text(i18n::t("tab.aur")))
+                .on_press(Message::TabChanged(Tab::Aur)),
             button(text(i18n::t("tab.settings")))
                 .on_press(Message::TabChanged(Tab::Settings)),
         ]
@@ -274,6 +811,7 @@ impl iced::Application for VistulaUpdater {
         let content = match self.current_tab {
             Tab::System => self.view_system_tab(),
             Tab::Flatpak => self.view_flatpak_tab(),
+            Tab::Aur => self.view_aur_tab(),
             Tab::Settings => self.view_settings_tab(),
         };
 
@@ -304,11 +842,17 @@ impl VistulaUpdater {
 
         let mut updates_list = column![];
         for update in &self.available_updates {
+            let source_label = match &update.source {
+                pacman::UpdateSource::Repo => "repo".to_string(),
+                pacman::UpdateSource::Aur => "aur".to_string(),
+                pacman::UpdateSource::Extension(name) => name.clone(),
+            };
             let update_row = row![
                 text(&update.name).width(Length::FillPortion(2)),
                 text(&update.current_version).width(Length::FillPortion(1)),
                 text("→"),
                 text(&update.new_version).width(Length::FillPortion(1)),
+                text(source_label).width(Length::FillPortion(1)),
             ]
             .spacing(10);
             updates_list = updates_list.push(update_row);
@@ -316,7 +860,7 @@ impl VistulaUpdater {
 
         let status = text(&self.system_status);
 
-        column![
+        let mut content = column![
             row![check_btn, update_btn].spacing(10),
             status,
             scrollable(
@@ -328,10 +872,74 @@ impl VistulaUpdater {
                 .spacing(5)
             ).height(Length::Fill),
         ]
-        .spacing(10)
+        .spacing(10);
+
+        if let Some(percent) = self.system_progress {
+            content = content.push(progress_bar(0.0..=100.0, percent as f32));
+        }
+        if !self.system_log.is_empty() {
+            content = content.push(self.view_log(&self.system_log));
+        }
+        if !self.pending_pacdiff.is_empty() {
+            content = content.push(self.view_pacdiff_prompt());
+        }
+        if let Some(hint) = self.view_error_hint(&self.system_error, Message::CheckUpdates) {
+            content = content.push(hint);
+        }
+
+        content.into()
+    }
+
+    /// A retry button for a [`AppError::Network`] failure, or a re-auth hint for
+    /// a [`AppError::PermissionDenied`] one. Other variants are already fully
+    /// described by the tab's status line, so they get no extra affordance.
+    fn view_error_hint(&self, error: &Option<AppError>, retry: Message) -> Option<Element<Message>> {
+        match error {
+            Some(AppError::Network(_)) => Some(
+                row![button(text(i18n::t("error.retry"))).on_press(retry)]
+                    .spacing(10)
+                    .into(),
+            ),
+            Some(AppError::PermissionDenied(_)) => Some(text(i18n::t("error.reauth_hint")).into()),
+            _ => None,
+        }
+    }
+
+    /// A prompt listing pending `.pacnew`/`.pacsave` files with a per-entry merge action
+    fn view_pacdiff_prompt(&self) -> Element<Message> {
+        let mut entries_list = column![].spacing(5);
+        for (index, entry) in self.pending_pacdiff.iter().enumerate() {
+            let kind_label = match entry.kind {
+                pacman::PacdiffKind::New => "pacnew",
+                pacman::PacdiffKind::Save => "pacsave",
+            };
+            let entry_row = row![
+                text(&entry.original).width(Length::Fill),
+                text(kind_label).width(Length::FillPortion(1)),
+                button(text(i18n::t("sys.pacdiff.merge"))).on_press(Message::MergePacdiff(index)),
+            ]
+            .spacing(10);
+            entries_list = entries_list.push(entry_row);
+        }
+
+        column![
+            text(i18n::t("sys.pacdiff.prompt")),
+            text(i18n::t("sys.pacdiff.warning")),
+            entries_list,
+        ]
+        .spacing(5)
         .into()
     }
 
+    /// A scrollable pane of output lines from a streamed operation
+    fn view_log<'a>(&self, lines: &'a [String]) -> Element<'a, Message> {
+        let mut log_col = column![].spacing(2);
+        for line in lines {
+            log_col = log_col.push(text(line));
+        }
+        scrollable(log_col).height(Length::Fixed(150.0)).into()
+    }
+
     fn view_flatpak_tab(&self) -> Element<Message> {
         let search_input = text_input(
             &i18n::t("fp.search.placeholder"),
@@ -350,9 +958,12 @@ impl VistulaUpdater {
         for app in &self.flatpak_search_results {
             let install_btn = button(text("Install"))
                 .on_press(Message::InstallFlatpak(app.appid.clone()));
-            
+            let details_btn = button(text(i18n::t("fp.details")))
+                .on_press(Message::ShowManifest(app.appid.clone()));
+
             let app_row = row![
                 text(&app.name).width(Length::Fill),
+                details_btn,
                 install_btn,
             ]
             .spacing(10);
@@ -361,20 +972,106 @@ impl VistulaUpdater {
 
         let status = text(&self.flatpak_status);
 
-        column![
+        let mut content = column![
             row![search_input, search_btn, list_installed_btn].spacing(10),
             status,
             scrollable(results_list).height(Length::Fill),
         ]
-        .spacing(10)
-        .into()
+        .spacing(10);
+
+        if let Some(percent) = self.flatpak_progress {
+            content = content.push(progress_bar(0.0..=100.0, percent as f32));
+        }
+        if !self.flatpak_log.is_empty() {
+            content = content.push(self.view_log(&self.flatpak_log));
+        }
+        if let Some(manifest) = &self.flatpak_manifest {
+            content = content.push(self.view_manifest(manifest));
+        }
+        if let Some(hint) = self.view_error_hint(&self.flatpak_error, Message::SearchFlatpaks) {
+            content = content.push(hint);
+        }
+
+        content.into()
+    }
+
+    /// Runtime/sdk/permission panel for the app last picked via "Details"
+    fn view_manifest<'a>(&self, (appid, manifest): &'a (String, flatpak::FlatpakManifest)) -> Element<'a, Message> {
+        let mut panel = column![
+            text(format!("{}: {}", appid, i18n::t("fp.manifest.title"))),
+            text(format!("{}: {}", i18n::t("fp.manifest.runtime"), manifest.runtime)),
+            text(format!("{}: {}", i18n::t("fp.manifest.sdk"), manifest.sdk)),
+        ]
+        .spacing(2);
+
+        if !manifest.finish_args.is_empty() {
+            panel = panel.push(text(format!("{}:", i18n::t("fp.manifest.permissions"))));
+            for arg in &manifest.finish_args {
+                panel = panel.push(text(format!("  {}", arg)));
+            }
+        }
+
+        panel.into()
+    }
+
+    fn view_aur_tab(&self) -> Element<Message> {
+        let search_input = text_input(
+            &i18n::t("aur.search.placeholder"),
+            &self.aur_search_query,
+        )
+        .on_input(Message::AurSearchQueryChanged)
+        .width(Length::FillPortion(4));
+
+        let search_btn = button(text(i18n::t("aur.search")))
+            .on_press(Message::SearchAur);
+
+        let mut results_list = column![];
+        for pkg in &self.aur_search_results {
+            let install_btn = button(text("Install"))
+                .on_press(Message::InstallAur(pkg.name.clone()));
+
+            let pkg_row = row![
+                text(&pkg.name).width(Length::FillPortion(1)),
+                text(&pkg.version).width(Length::FillPortion(1)),
+                text(pkg.description.as_deref().unwrap_or("")).width(Length::FillPortion(2)),
+                install_btn,
+            ]
+            .spacing(10);
+            results_list = results_list.push(pkg_row);
+        }
+
+        let status = text(&self.aur_status);
+
+        let mut content = column![
+            row![search_input, search_btn].spacing(10),
+            status,
+            scrollable(results_list).height(Length::Fill),
+        ]
+        .spacing(10);
+
+        if let Some(percent) = self.aur_progress {
+            content = content.push(progress_bar(0.0..=100.0, percent as f32));
+        }
+        if !self.aur_log.is_empty() {
+            content = content.push(self.view_log(&self.aur_log));
+        }
+        if let Some(hint) = self.view_error_hint(&self.aur_error, Message::SearchAur) {
+            content = content.push(hint);
+        }
+
+        content.into()
     }
 
     fn view_settings_tab(&self) -> Element<Message> {
         column![
             row![
                 text(i18n::t("settings.language")).width(Length::FillPortion(1)),
-                text(&self.config.language).width(Length::FillPortion(1)),
+                pick_list(
+                    self.available_languages.clone(),
+                    Some(self.config.language.clone()),
+                    Message::LanguageChanged,
+                )
+                .width(Length::FillPortion(1)),
             ]
             .spacing(10)
             .align_items(Alignment::Center),
@@ -384,6 +1081,20 @@ impl VistulaUpdater {
             ]
             .spacing(10)
             .align_items(Alignment::Center),
+            row![
+                text(i18n::t("settings.sudoloop")).width(Length::FillPortion(1)),
+                toggler(None, self.config.sudoloop_enabled, Message::SudoloopToggled)
+                    .width(Length::Shrink),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+            row![
+                text(i18n::t("settings.pacdiff_check")).width(Length::FillPortion(1)),
+                toggler(None, self.config.pacdiff_check_enabled, Message::PacdiffCheckToggled)
+                    .width(Length::Shrink),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
         ]
         .spacing(20)
         .into()
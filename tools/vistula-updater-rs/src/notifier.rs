@@ -1,30 +1,96 @@
 mod cinnamon;
 mod commands;
 mod config;
+mod error;
 mod i18n;
 
-use anyhow::Result;
+use error::AppExitCode;
+use std::collections::HashMap;
 use std::time::Duration;
 use tokio::time::sleep;
 
 /// Background notifier that checks for system updates periodically
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     tracing_subscriber::fmt::init();
 
     let args: Vec<String> = std::env::args().collect();
-    
-    if args.len() > 1 && args[1] == "once" {
-        check_updates_once().await?;
+
+    let result = if args.len() > 1 && args[1] == "once" {
+        check_updates_once().await
+    } else if args.len() > 1 && args[1] == "info" {
+        print_info_report();
+        Ok(())
     } else {
-        check_updates_loop().await?;
+        check_updates_loop().await
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(AppExitCode::from_error(&e).code());
     }
+}
 
-    Ok(())
+/// A single row of the `info` diagnostic report
+struct ToolStatus {
+    name: &'static str,
+    version_flag: &'static str,
+    hint: &'static str,
+}
+
+const DIAGNOSED_TOOLS: &[ToolStatus] = &[
+    ToolStatus { name: "checkupdates", version_flag: "--version", hint: "install: pacman-contrib" },
+    ToolStatus { name: "pacman", version_flag: "--version", hint: "install: pacman" },
+    ToolStatus { name: "flatpak", version_flag: "--version", hint: "install: flatpak" },
+    ToolStatus { name: "notify-send", version_flag: "--version", hint: "install: libnotify" },
+    ToolStatus { name: "gsettings", version_flag: "--version", hint: "install: glib2" },
+];
+
+/// Parse a tool's `--version` output into a short version string.
+///
+/// Most tools put this on the first line, but `pacman --version` leads with
+/// a blank line and several lines of ASCII art before the version appears
+/// (as `v6.0.2`), so this scans every line for the first whitespace-separated
+/// token containing a digit instead of assuming the first line has it.
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.split_whitespace().find(|tok| tok.chars().any(|c| c.is_ascii_digit())))
+        .map(|v| v.to_string())
+        .or_else(|| output.lines().find(|line| !line.trim().is_empty()).map(|line| line.trim().to_string()))
+}
+
+/// Print a diagnostic report of tool availability, versions, and resolved paths
+fn print_info_report() {
+    println!("VistulaOS Updater — environment report");
+    println!();
+    println!("{:<15} {:<8} {:<15} {}", "tool", "found", "version", "hint");
+    for tool in DIAGNOSED_TOOLS {
+        let found = commands::have_command(tool.name);
+        let version = if found {
+            commands::run_command(tool.name, &[tool.version_flag], false)
+                .ok()
+                .and_then(|r| parse_version(&r.stdout))
+                .unwrap_or_else(|| "unknown".to_string())
+        } else {
+            "-".to_string()
+        };
+        let hint = if found { "" } else { tool.hint };
+        println!("{:<15} {:<8} {:<15} {}", tool.name, found, version, hint);
+    }
+
+    println!();
+    println!("config dir:    {}", config::config_dir().display());
+    println!("assets path:   {}", i18n::assets_path().display());
+    println!("language:      {}", i18n::current_language());
+    println!(
+        "cinnamon theme: {}",
+        cinnamon::read_cinnamon_theme().unwrap_or_else(|| "unknown".to_string())
+    );
 }
 
 /// Check updates once and exit
-async fn check_updates_once() -> Result<()> {
+async fn check_updates_once() -> Result<(), error::AppError> {
     if !commands::have_command("checkupdates") {
         eprintln!("Error: checkupdates not found. Install: pacman-contrib");
         return Ok(());
@@ -33,12 +99,9 @@ async fn check_updates_once() -> Result<()> {
     match commands::run_command("checkupdates", &[], false) {
         Ok(result) => {
             if !result.stdout.is_empty() {
-                let count = result.stdout.lines().count();
-                show_notification(&format!(
-                    "{}: {}",
-                    i18n::t("notify.title"),
-                    count
-                ));
+                let count = result.stdout.lines().count() as i64;
+                let message = i18n::t_plural("notify.updates_count", count, &HashMap::new());
+                show_notification(&message);
             }
         }
         Err(e) => eprintln!("Failed to check updates: {}", e),
@@ -48,7 +111,7 @@ async fn check_updates_once() -> Result<()> {
 }
 
 /// Check updates periodically (every hour)
-async fn check_updates_loop() -> Result<()> {
+async fn check_updates_loop() -> Result<(), error::AppError> {
     loop {
         check_updates_once().await?;
         sleep(Duration::from_secs(3600)).await;
@@ -61,3 +124,34 @@ fn show_notification(message: &str) {
         .args(&["--urgency=normal", "VistulaOS Updater", message])
         .output();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_simple_first_line() {
+        assert_eq!(parse_version("flatpak 1.14.4\n"), Some("1.14.4".to_string()));
+    }
+
+    #[test]
+    fn test_parse_version_pacman_banner() {
+        let output = "\n .--.                  Pacman v6.0.2 - libalpm v13.0.2\n\
+                      /    \\  Copyright (C) 2006-2021 Pacman Development Team\n\
+                      \\    /  Copyright (C) 2002-2006 Judd Vinet\n\
+                      '--'\n\
+                      \\_.'\n";
+        assert_eq!(parse_version(output), Some("v6.0.2".to_string()));
+    }
+
+    #[test]
+    fn test_parse_version_no_digit_falls_back_to_first_nonblank_line() {
+        let output = "unknown tool banner\nwith no version info\n";
+        assert_eq!(parse_version(output), Some("unknown tool banner".to_string()));
+    }
+
+    #[test]
+    fn test_parse_version_empty() {
+        assert_eq!(parse_version(""), None);
+    }
+}
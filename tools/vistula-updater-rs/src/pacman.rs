@@ -1,9 +1,23 @@
+use crate::commands::{CommandEvent, ShellCommand};
+use crate::error::AppError;
+use std::cmp::Ordering;
+
+/// Where a [`PackageUpdate`] comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateSource {
+    Repo,
+    Aur,
+    /// Reported by a loaded WASM extension, named by [`Backend::name`](crate::backend::Backend::name)
+    Extension(String),
+}
+
 /// Represents an available system update
 #[derive(Debug, Clone)]
 pub struct PackageUpdate {
     pub name: String,
     pub current_version: String,
     pub new_version: String,
+    pub source: UpdateSource,
 }
 
 /// Parse checkupdates output into structured updates
@@ -18,6 +32,7 @@ pub fn parse_updates(output: &str) -> Vec<PackageUpdate> {
                     name: parts[0].to_string(),
                     current_version: parts[1].to_string(),
                     new_version: parts[3].to_string(),
+                    source: UpdateSource::Repo,
                 })
             } else {
                 None
@@ -27,16 +42,226 @@ pub fn parse_updates(output: &str) -> Vec<PackageUpdate> {
 }
 
 /// Check for system updates using checkupdates
-pub async fn check_for_updates() -> Result<Vec<PackageUpdate>, String> {
-    let result = crate::commands::run_command("checkupdates", &[], false)
-        .map_err(|e| e.to_string())?;
-    Ok(parse_updates(&result.stdout))
+pub async fn check_for_updates() -> Result<Vec<PackageUpdate>, AppError> {
+    // checkupdates exits non-zero when there's simply nothing to update, so
+    // its exit code isn't checked here, unlike mutating commands below.
+    let result = ShellCommand::new("checkupdates").wait()?;
+    let mut updates = parse_updates(&result.stdout);
+    updates.extend(check_aur_updates().await?);
+    Ok(updates)
 }
 
 /// Update system using pacman with elevation
-pub async fn update_system() -> Result<(), String> {
-    crate::commands::run_command("pacman", &["-Syu"], true)
-        .map_err(|e| e.to_string())?;
+pub async fn update_system() -> Result<(), AppError> {
+    ShellCommand::new("pacman")
+        .arg("-Syu")
+        .elevated(true)
+        .wait()?
+        .into_result()?;
+    Ok(())
+}
+
+/// List foreign packages (installed from the AUR or manually) as (name, version) pairs
+pub fn list_foreign_packages() -> Result<Vec<(String, String)>, AppError> {
+    // `pacman -Qm` exits non-zero when there are simply no foreign packages installed.
+    let result = ShellCommand::new("pacman").arg("-Qm").wait()?;
+    Ok(result
+        .stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next()?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect())
+}
+
+/// A single entry from the AUR RPC `info` response
+#[derive(Debug, Clone, serde::Deserialize)]
+struct AurInfoResult {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Version")]
+    version: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct AurInfoResponse {
+    results: Vec<AurInfoResult>,
+}
+
+const AUR_RPC_URL: &str = "https://aur.archlinux.org/rpc/?v=5&type=info";
+
+/// Query the AUR RPC v5 `info` endpoint for a batch of package names
+async fn query_aur_info(pkgs: &[String]) -> Result<Vec<AurInfoResult>, AppError> {
+    if pkgs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut url = AUR_RPC_URL.to_string();
+    for pkg in pkgs {
+        url.push_str("&arg[]=");
+        url.push_str(pkg);
+    }
+
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?
+        .json::<AurInfoResponse>()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    Ok(response.results)
+}
+
+/// Compare two pacman-style version strings the way `vercmp` does: split into
+/// alternating alpha/numeric segments and compare segment-by-segment, with
+/// numeric segments compared numerically.
+pub fn vercmp(a: &str, b: &str) -> Ordering {
+    fn segments(v: &str) -> Vec<String> {
+        let mut segs = Vec::new();
+        let mut current = String::new();
+        let mut current_is_digit: Option<bool> = None;
+
+        for c in v.chars() {
+            if !c.is_alphanumeric() {
+                if !current.is_empty() {
+                    segs.push(std::mem::take(&mut current));
+                }
+                current_is_digit = None;
+                continue;
+            }
+            let is_digit = c.is_ascii_digit();
+            if current_is_digit.is_some() && current_is_digit != Some(is_digit) {
+                segs.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+            current_is_digit = Some(is_digit);
+        }
+        if !current.is_empty() {
+            segs.push(current);
+        }
+        segs
+    }
+
+    let a_segs = segments(a);
+    let b_segs = segments(b);
+
+    for pair in a_segs.iter().zip(b_segs.iter()) {
+        let (sa, sb) = pair;
+        let ordering = match (sa.parse::<u64>(), sb.parse::<u64>()) {
+            (Ok(na), Ok(nb)) => na.cmp(&nb),
+            _ => sa.cmp(sb),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    a_segs.len().cmp(&b_segs.len())
+}
+
+/// Check for AUR package updates by diffing installed foreign packages against the AUR RPC
+pub async fn check_aur_updates() -> Result<Vec<PackageUpdate>, AppError> {
+    let foreign = list_foreign_packages()?;
+    if foreign.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let names: Vec<String> = foreign.iter().map(|(name, _)| name.clone()).collect();
+    let results = query_aur_info(&names).await?;
+
+    let mut updates = Vec::new();
+    for (name, installed_version) in foreign {
+        if let Some(remote) = results.iter().find(|r| r.name == name) {
+            if vercmp(&remote.version, &installed_version) == Ordering::Greater {
+                updates.push(PackageUpdate {
+                    name,
+                    current_version: installed_version,
+                    new_version: remote.version.clone(),
+                    source: UpdateSource::Aur,
+                });
+            }
+        }
+    }
+    Ok(updates)
+}
+
+/// Update an AUR package by cloning/pulling its git repo and running
+/// `makepkg -si`.
+///
+/// This delegates to [`crate::aur::install_streaming`] and drains its events
+/// to completion rather than keeping its own copy of the clone/pull +
+/// `makepkg` flow, so the two call sites (this blocking catch-up path and the
+/// streaming single-package install used by the AUR tab) can't drift apart
+/// the way they previously did.
+pub async fn update_aur(pkg: &str) -> Result<(), AppError> {
+    let mut rx = crate::aur::install_streaming(pkg).await?;
+    while let Some(event) = rx.recv().await {
+        if let CommandEvent::Finished { status } = event {
+            return status.into_result().map(|_| ());
+        }
+    }
+    Err(AppError::Other(format!(
+        "AUR install for {pkg} ended without a final status"
+    )))
+}
+
+/// Whether a pending merge file is a `.pacnew` (new upstream config) or `.pacsave`
+/// (old config kept aside after the package no longer installs it)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacdiffKind {
+    New,
+    Save,
+}
+
+/// A `.pacnew`/`.pacsave` file left behind by a package upgrade, still awaiting a merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacdiffEntry {
+    pub path: String,
+    pub kind: PacdiffKind,
+    /// The original, currently-active config file this entry should be merged into
+    pub original: String,
+}
+
+/// Parse `pacdiff --output` (one pending file path per line) into structured entries
+fn parse_pacdiff_output(output: &str) -> Vec<PacdiffEntry> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|path| {
+            let (original, kind) = if let Some(stem) = path.strip_suffix(".pacnew") {
+                (stem, PacdiffKind::New)
+            } else if let Some(stem) = path.strip_suffix(".pacsave") {
+                (stem, PacdiffKind::Save)
+            } else {
+                return None;
+            };
+            Some(PacdiffEntry {
+                path: path.to_string(),
+                kind,
+                original: original.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Scan for pending `.pacnew`/`.pacsave` files left behind by package upgrades
+pub fn scan_pacdiff() -> Result<Vec<PacdiffEntry>, AppError> {
+    let result = ShellCommand::new("pacdiff").arg("--output").wait()?;
+    Ok(parse_pacdiff_output(&result.stdout))
+}
+
+/// Launch `merge_tool` on a single pending entry against its original config file.
+/// Runs elevated, since the files under `/etc` this touches are root-owned.
+pub fn merge_pacdiff_entry(entry: &PacdiffEntry, merge_tool: &str) -> Result<(), AppError> {
+    ShellCommand::new(merge_tool)
+        .args([entry.original.as_str(), entry.path.as_str()])
+        .elevated(true)
+        .wait()?
+        .into_result()?;
     Ok(())
 }
 
@@ -83,9 +308,36 @@ mod tests {
             name: "test".to_string(),
             current_version: "1.0".to_string(),
             new_version: "2.0".to_string(),
+            source: UpdateSource::Repo,
         };
         assert_eq!(update.name, "test");
         assert_eq!(update.current_version, "1.0");
         assert_eq!(update.new_version, "2.0");
     }
+
+    #[test]
+    fn test_vercmp() {
+        assert_eq!(vercmp("1.0.1", "1.0.0"), Ordering::Greater);
+        assert_eq!(vercmp("1.0.0", "1.0.1"), Ordering::Less);
+        assert_eq!(vercmp("1.2", "1.2"), Ordering::Equal);
+        assert_eq!(vercmp("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(vercmp("1.0", "1.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_parse_pacdiff_output() {
+        let output = "/etc/pacman.conf.pacnew\n/etc/locale.gen.pacsave\n\n";
+        let entries = parse_pacdiff_output(output);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].original, "/etc/pacman.conf");
+        assert_eq!(entries[0].kind, PacdiffKind::New);
+        assert_eq!(entries[1].original, "/etc/locale.gen");
+        assert_eq!(entries[1].kind, PacdiffKind::Save);
+    }
+
+    #[test]
+    fn test_parse_pacdiff_output_empty() {
+        assert_eq!(parse_pacdiff_output("").len(), 0);
+    }
 }